@@ -1,5 +1,12 @@
 use super::*;
 
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+const CALLBACK_BASE_ID: i32 = 1000;
+
 /// An id for a steam app/game
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -10,7 +17,36 @@ impl From<u32> for AppId {
     }
 }
 
+/// An id for a depot of a steam app
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DepotId(pub u32);
+
+/// Information about a DLC of the current app
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DlcData {
+    pub app_id: AppId,
+    /// Whether the DLC is currently available on the store
+    pub available: bool,
+    pub name: String,
+}
+
+/// Steam's records of a file shipped with the current app
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileDetails {
+    /// The size of the file in bytes
+    pub size: u64,
+    /// The SHA1 hash of the file
+    pub sha1: [u8; 20],
+    pub flags: u32,
+}
+
 /// Access to the steam apps interface
+///
+/// Created by [`Client::apps`]. This covers ownership checks, e.g. gating DLC
+/// content behind [`is_dlc_installed`](#method.is_dlc_installed).
 pub struct Apps<Manager> {
     pub(crate) apps: *mut sys::ISteamApps,
     pub(crate) _inner: Arc<Inner<Manager>>,
@@ -40,12 +76,23 @@ impl<Manager> Apps<Manager> {
         unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedApp(self.apps, app_id.0) }
     }
 
+    /// Returns when the user first bought the app with the given ID, in
+    /// seconds since the unix epoch.
+    ///
+    /// Returns `0` if the user doesn't own the app.
+    pub fn earliest_purchase_unix_time(&self, app_id: AppId) -> u32 {
+        unsafe { sys::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(self.apps, app_id.0) }
+    }
+
     /// Returns whether the user is subscribed via a free weekend
     pub fn is_subscribed_from_free_weekend(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(self.apps) }
     }
 
     /// Returns whether the user has a VAC ban on their account.
+    ///
+    /// This only covers bans for the VAC-enabled components of the current
+    /// app, e.g. to keep a banned player out of ranked play.
     pub fn is_vac_banned(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamApps_BIsVACBanned(self.apps) }
     }
@@ -58,6 +105,8 @@ impl<Manager> Apps<Manager> {
 
     /// Returns whether the license for the current app ID
     /// provides low violence depots.
+    ///
+    /// This is set in some regions, where the game should e.g. hide gore.
     pub fn is_low_violence(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamApps_BIsLowViolence(self.apps) }
     }
@@ -75,19 +124,42 @@ impl<Manager> Apps<Manager> {
     /// Returns the installation folder of the app with the given ID.
     ///
     /// This works even if the app isn't installed, returning where it
-    /// would be installed in the default location.
-    pub fn app_install_dir(&self, app_id: AppId) -> String {
-        unsafe {
-            let mut buffer = vec![0; 2048];
-            sys::SteamAPI_ISteamApps_GetAppInstallDir(
-                self.apps,
-                app_id.0,
-                buffer.as_mut_ptr(),
-                buffer.len() as u32,
-            );
-            let path = CStr::from_ptr(buffer.as_ptr());
-            path.to_string_lossy().into_owned()
-        }
+    /// would be installed in the default location. Returns `None` if steam
+    /// reports no folder for the app.
+    pub fn app_install_dir(&self, app_id: AppId) -> Option<PathBuf> {
+        unsafe { read_app_install_dir(self.apps, app_id) }
+    }
+
+    /// Returns the installation folder of the app with the given ID without
+    /// blocking the calling thread.
+    ///
+    /// `GetAppInstallDir` reads from disk and can be slow on large libraries
+    /// or network drives, so this runs it on a dedicated thread. This is
+    /// plain blocking I/O moved off the current thread, not a steam call
+    /// result, so it doesn't need [`Client::run_callbacks`] to complete.
+    ///
+    /// Resolves to `None` if steam reports no folder for the app.
+    pub fn app_install_dir_async(&self, app_id: AppId) -> impl Future<Output = Option<PathBuf>>
+    where
+        Manager: Send + Sync + 'static,
+    {
+        let shared = Arc::new(Mutex::new(InstallDirState {
+            result: None,
+            waker: None,
+        }));
+        let thread_shared = shared.clone();
+        let inner = self._inner.clone();
+        std::thread::spawn(move || {
+            let dir = unsafe { read_app_install_dir(sys::SteamAPI_SteamApps_v008(), app_id) };
+            // Keep the client alive until the call returns
+            drop(inner);
+            let mut state = thread_shared.lock().unwrap();
+            state.result = Some(dir);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        AppInstallDirFuture { shared }
     }
 
     /// Returns the steam id of the original owner of the app.
@@ -98,12 +170,19 @@ impl<Manager> Apps<Manager> {
     }
 
     /// Returns a list of languages that the current app supports.
+    ///
+    /// The languages are steam api names, e.g. `"english"`.
     pub fn available_game_languages(&self) -> Vec<String> {
         unsafe {
             let langs = sys::SteamAPI_ISteamApps_GetAvailableGameLanguages(self.apps);
             let langs = CStr::from_ptr(langs);
             let langs = langs.to_string_lossy();
-            langs.split(',').map(|v| v.to_owned()).collect()
+            langs
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_owned())
+                .collect()
         }
     }
 
@@ -156,4 +235,221 @@ impl<Manager> Apps<Manager> {
             command_line.to_string_lossy().into_owned()
         }
     }
+
+    /// Returns the value of a launch query parameter, e.g. `"connect"` when
+    /// the game was launched through `steam://run/<app id>//?connect=...`.
+    ///
+    /// Returns `None` if the parameter wasn't set.
+    pub fn get_launch_query_param(&self, key: &str) -> Option<String> {
+        let key = CString::new(key).ok()?;
+        unsafe {
+            let value = sys::SteamAPI_ISteamApps_GetLaunchQueryParam(self.apps, key.as_ptr());
+            if value.is_null() {
+                return None;
+            }
+            let value = CStr::from_ptr(value);
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Requests Steam's records of a file shipped with the current app, e.g.
+    /// to check a local file hasn't been modified.
+    ///
+    /// `filename` is relative to the install folder of the app. `cb` receives
+    /// [`SteamError::InvalidName`] right away if it contains a nul byte.
+    pub fn get_file_details<F>(&self, filename: &str, cb: F)
+    where
+        F: FnOnce(Result<FileDetails, SteamError>) + 'static + Send,
+    {
+        let filename = match CString::new(filename) {
+            Ok(filename) => filename,
+            Err(_) => return cb(Err(SteamError::InvalidName)),
+        };
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamApps_GetFileDetails(self.apps, filename.as_ptr());
+            register_call_result::<sys::FileDetailsResult_t, _, _>(
+                &self._inner,
+                api_call,
+                CALLBACK_BASE_ID + 23,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(FileDetails {
+                            size: v.m_ulFileSize,
+                            sha1: v.m_FileSHA,
+                            flags: v.m_unFlags,
+                        })
+                    })
+                },
+            );
+        }
+    }
+
+    /// Returns the installed depots of the app with the given ID, in mount
+    /// order, up to `max` of them.
+    pub fn get_installed_depots(&self, app_id: AppId, max: u32) -> Vec<DepotId> {
+        unsafe {
+            let mut depots = vec![0; max as usize];
+            let count = sys::SteamAPI_ISteamApps_GetInstalledDepots(
+                self.apps,
+                app_id.0,
+                depots.as_mut_ptr(),
+                max,
+            );
+            depots.truncate(count.min(max) as usize);
+            depots.into_iter().map(DepotId).collect()
+        }
+    }
+
+    /// Tells Steam the files of the current app are corrupt, so it validates
+    /// them the next time the game isn't running.
+    ///
+    /// With `missing_files_only` only missing files are restored instead of
+    /// checking every file.
+    pub fn mark_content_corrupt(&self, missing_files_only: bool) -> bool {
+        unsafe { sys::SteamAPI_ISteamApps_MarkContentCorrupt(self.apps, missing_files_only) }
+    }
+
+    /// Returns the number of DLCs of the current app.
+    pub fn dlc_count(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamApps_GetDLCCount(self.apps).max(0) as u32 }
+    }
+
+    /// Returns the data of the DLC at `index`, `None` if `index` isn't less
+    /// than [`dlc_count`](#method.dlc_count).
+    pub fn dlc(&self, index: u32) -> Option<DlcData> {
+        if index >= self.dlc_count() {
+            return None;
+        }
+        unsafe {
+            let mut app_id = 0;
+            let mut available = false;
+            let mut buffer = vec![0; 128];
+            if sys::SteamAPI_ISteamApps_BGetDLCDataByIndex(
+                self.apps,
+                index as _,
+                &mut app_id,
+                &mut available,
+                buffer.as_mut_ptr(),
+                buffer.len() as _,
+            ) {
+                let name = CStr::from_ptr(buffer.as_ptr());
+                Some(DlcData {
+                    app_id: AppId(app_id),
+                    available,
+                    name: name.to_string_lossy().into_owned(),
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the data of every DLC of the current app.
+    pub fn all_dlc(&self) -> Vec<DlcData> {
+        (0..self.dlc_count())
+            .filter_map(|idx| self.dlc(idx))
+            .collect()
+    }
+
+    /// Starts downloading and installing a DLC the user owns.
+    ///
+    /// Steam sends a [`DlcInstalled`] callback once it is installed, progress
+    /// can be polled with [`dlc_download_progress`](#method.dlc_download_progress).
+    pub fn install_dlc(&self, app_id: AppId) {
+        unsafe { sys::SteamAPI_ISteamApps_InstallDLC(self.apps, app_id.0) }
+    }
+
+    /// Uninstalls a DLC.
+    pub fn uninstall_dlc(&self, app_id: AppId) {
+        unsafe { sys::SteamAPI_ISteamApps_UninstallDLC(self.apps, app_id.0) }
+    }
+
+    /// Returns the bytes downloaded and the total bytes of a DLC that is
+    /// being downloaded, `None` if it isn't downloading.
+    pub fn dlc_download_progress(&self, app_id: AppId) -> Option<(u64, u64)> {
+        unsafe {
+            let mut downloaded = 0;
+            let mut total = 0;
+            if sys::SteamAPI_ISteamApps_GetDlcDownloadProgress(
+                self.apps,
+                app_id.0,
+                &mut downloaded,
+                &mut total,
+            ) {
+                Some((downloaded, total))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Callback sent when a DLC has been installed
+///
+/// Sent both for DLC installed through [`Apps::install_dlc`] and DLC the user
+/// bought while playing.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DlcInstalled {
+    pub app_id: AppId,
+}
+
+unsafe impl Callback for DlcInstalled {
+    const ID: i32 = CALLBACK_BASE_ID + 5;
+    const SIZE: i32 = std::mem::size_of::<sys::DlcInstalled_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::DlcInstalled_t);
+        DlcInstalled {
+            app_id: AppId(val.m_nAppID),
+        }
+    }
+}
+
+unsafe fn read_app_install_dir(apps: *mut sys::ISteamApps, app_id: AppId) -> Option<PathBuf> {
+    let mut buffer = vec![0; 2048];
+    let len = sys::SteamAPI_ISteamApps_GetAppInstallDir(
+        apps,
+        app_id.0,
+        buffer.as_mut_ptr(),
+        buffer.len() as u32,
+    );
+    let path = CStr::from_ptr(buffer.as_ptr());
+    if len == 0 || path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path.to_string_lossy().into_owned()))
+    }
+}
+
+struct InstallDirState {
+    result: Option<Option<PathBuf>>,
+    waker: Option<Waker>,
+}
+
+struct AppInstallDirFuture {
+    shared: Arc<Mutex<InstallDirState>>,
+}
+
+impl Future for AppInstallDirFuture {
+    type Output = Option<PathBuf>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock().unwrap();
+        match state.result.take() {
+            Some(dir) => Poll::Ready(dir),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
 }
@@ -58,6 +58,105 @@ where
     }
 }
 
+/// An internal listener for a callback type, kept for as long as it returns `true`
+pub(crate) struct Listener {
+    token: ListenerToken,
+    f: Box<dyn FnMut(*mut c_void) -> bool + Send + 'static>,
+}
+
+impl Listener {
+    /// Calls the listener unless it was removed, returning whether it is kept
+    pub(crate) fn call(&mut self, param: *mut c_void) -> bool {
+        if !self.token.is_active() {
+            return false;
+        }
+        let keep = (self.f)(param);
+        if !keep {
+            self.token.remove();
+        }
+        keep
+    }
+}
+
+/// Shared by listeners registered together, which are all removed once one
+/// of them returns `false` or the token is removed
+#[derive(Clone)]
+pub(crate) struct ListenerToken(Arc<AtomicBool>);
+
+impl ListenerToken {
+    pub(crate) fn new() -> Self {
+        ListenerToken(Arc::new(AtomicBool::new(true)))
+    }
+
+    fn is_active(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Stops the listeners from being called again, they are dropped the
+    /// next time callbacks are dispatched
+    pub(crate) fn remove(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl Callbacks {
+    /// Drops the listeners whose token was removed
+    pub(crate) fn remove_inactive_listeners(&mut self) {
+        for listeners in self.listeners.values_mut() {
+            listeners.retain(|l| l.token.is_active());
+        }
+    }
+}
+
+/// Removes the listeners sharing `token` right away, or with the next
+/// dispatch if the callbacks are busy
+pub(crate) fn remove_listeners<Manager>(inner: &Arc<Inner<Manager>>, token: &ListenerToken) {
+    token.remove();
+    if let Ok(mut callbacks) = inner.callbacks.try_lock() {
+        callbacks.remove_inactive_listeners();
+    }
+}
+
+/// Registers a listener used by the crate itself to observe callbacks.
+///
+/// Unlike `register_callback` this doesn't replace a callback the user
+/// registered for the same type. Listeners run before the user's callback
+/// and are removed once they return `false`.
+pub(crate) unsafe fn register_listener<C, F, Manager>(
+    inner: &Arc<Inner<Manager>>,
+    f: F,
+) -> ListenerToken
+where
+    C: Callback,
+    F: FnMut(C) -> bool + Send + 'static,
+{
+    let token = ListenerToken::new();
+    add_listener(&mut inner.callbacks.lock().unwrap(), &token, f);
+    token
+}
+
+/// Like `register_listener`, for callbacks that are already locked, e.g. to
+/// register before starting the request so its response can't be missed.
+///
+/// The listener is removed together with the others sharing `token`.
+pub(crate) unsafe fn add_listener<C, F>(callbacks: &mut Callbacks, token: &ListenerToken, mut f: F)
+where
+    C: Callback,
+    F: FnMut(C) -> bool + Send + 'static,
+{
+    callbacks
+        .listeners
+        .entry(C::ID)
+        .or_default()
+        .push(Listener {
+            token: token.clone(),
+            f: Box::new(move |param| {
+                let param = C::from_raw(param);
+                f(param)
+            }),
+        });
+}
+
 pub(crate) unsafe fn register_call_result<C, F, Manager>(
     inner: &Arc<Inner<Manager>>,
     api_call: sys::SteamAPICall_t,
@@ -733,6 +733,317 @@ impl TryFrom<i64> for SteamError {
     }
 }
 
+/// Errors from reading or writing the current user's stats and achievements
+///
+/// Steam only reports whether these calls succeeded, so the cause is
+/// inferred from what the crate knows about the current state.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UserStatsError {
+    /// Returned when the current user's stats haven't been received yet
+    ///
+    /// `request_current_stats` has to complete successfully first.
+    #[error("the current user's stats haven't been received yet")]
+    StatsNotLoaded,
+    /// Returned when no achievement exists with the given API name
+    #[error("achievement not found")]
+    AchievementNotFound,
+    /// Returned when no stat exists with the given API name
+    #[error("stat not found")]
+    StatNotFound,
+    /// Returned when the stat exists but was used as the wrong type, e.g. an
+    /// integer stat read as a float
+    #[error("stat used as the wrong type")]
+    WrongType,
+    /// Returned when the API name contains a nul byte
+    #[error("invalid stat name")]
+    InvalidName,
+    /// Returned when Steam refused to queue a store although the stats were received
+    #[error("the stats couldn't be stored")]
+    StoreFailed,
+}
+
+/// Errors from the voice api
+///
+/// Mapped from the `EVoiceResult` codes steam returns.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VoiceError {
+    /// Returned when the steam voice system isn't initialized
+    #[error("the voice system is not initialized")]
+    NotInitialized,
+    /// Returned when voice recording isn't active
+    #[error("voice recording is not active")]
+    NotRecording,
+    /// Returned when there is no voice data available yet
+    #[error("no voice data available")]
+    NoData,
+    /// Returned when the passed buffer was too small for the voice data
+    #[error("buffer too small for the voice data")]
+    BufferTooSmall,
+    /// Returned when the voice data is corrupted
+    #[error("voice data corrupted")]
+    DataCorrupted,
+    /// Returned when the user is restricted from using voice chat
+    #[error("voice chat restricted")]
+    Restricted,
+    /// Returned when the voice data uses an unsupported codec
+    #[error("unsupported voice codec")]
+    UnsupportedCodec,
+    /// Returned when the receiver's steam client is out of date
+    #[error("the receiver is out of date")]
+    ReceiverOutOfDate,
+    /// Returned when the receiver didn't answer
+    #[error("the receiver did not answer")]
+    ReceiverDidNotAnswer,
+}
+
+impl From<sys::EVoiceResult> for VoiceError {
+    fn from(r: sys::EVoiceResult) -> Self {
+        match r {
+            sys::EVoiceResult::k_EVoiceResultOK => {
+                panic!("EVoiceResult::k_EVoiceResultOK isn't an error")
+            }
+            sys::EVoiceResult::k_EVoiceResultNotInitialized => VoiceError::NotInitialized,
+            sys::EVoiceResult::k_EVoiceResultNotRecording => VoiceError::NotRecording,
+            sys::EVoiceResult::k_EVoiceResultNoData => VoiceError::NoData,
+            sys::EVoiceResult::k_EVoiceResultBufferTooSmall => VoiceError::BufferTooSmall,
+            sys::EVoiceResult::k_EVoiceResultDataCorrupted => VoiceError::DataCorrupted,
+            sys::EVoiceResult::k_EVoiceResultRestricted => VoiceError::Restricted,
+            sys::EVoiceResult::k_EVoiceResultUnsupportedCodec => VoiceError::UnsupportedCodec,
+            sys::EVoiceResult::k_EVoiceResultReceiverOutOfDate => VoiceError::ReceiverOutOfDate,
+            sys::EVoiceResult::k_EVoiceResultReceiverDidNotAnswer => {
+                VoiceError::ReceiverDidNotAnswer
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Reasons a rich presence update can be refused
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RichPresenceError {
+    /// The key is longer than [`MAX_RICH_PRESENCE_KEY_LENGTH`](crate::MAX_RICH_PRESENCE_KEY_LENGTH) allows
+    #[error("rich presence key is too long")]
+    KeyTooLong,
+    /// The value is longer than [`MAX_RICH_PRESENCE_VALUE_LENGTH`](crate::MAX_RICH_PRESENCE_VALUE_LENGTH) allows
+    #[error("rich presence value is too long")]
+    ValueTooLong,
+    /// Setting the key would exceed [`MAX_RICH_PRESENCE_KEYS`](crate::MAX_RICH_PRESENCE_KEYS)
+    #[error("too many rich presence keys")]
+    TooManyKeys,
+    /// The key or value contains a nul byte
+    #[error("rich presence key or value contains a nul byte")]
+    NulByte,
+    /// Steam refused the update for another reason
+    #[error("steam rejected the rich presence update")]
+    Rejected,
+}
+
+/// Reasons an auth session can't be started
+///
+/// Mapped from the `EBeginAuthSessionResult` codes steam returns.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AuthSessionError {
+    /// The ticket is invalid
+    #[error("invalid auth session ticket")]
+    InvalidTicket,
+    /// A session was already started for the user
+    #[error("duplicate auth session request")]
+    DuplicateRequest,
+    /// The ticket was created by an incompatible interface version
+    #[error("invalid auth session ticket version")]
+    InvalidVersion,
+    /// The ticket was created for a different game
+    #[error("auth session ticket is for a different game")]
+    GameMismatch,
+    /// The ticket has expired
+    #[error("auth session ticket expired")]
+    ExpiredTicket,
+}
+
+impl From<sys::EBeginAuthSessionResult> for AuthSessionError {
+    fn from(r: sys::EBeginAuthSessionResult) -> Self {
+        match r {
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultOK => {
+                panic!("EBeginAuthSessionResult::k_EBeginAuthSessionResultOK isn't an error")
+            }
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultInvalidTicket => {
+                AuthSessionError::InvalidTicket
+            }
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultDuplicateRequest => {
+                AuthSessionError::DuplicateRequest
+            }
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultInvalidVersion => {
+                AuthSessionError::InvalidVersion
+            }
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultGameMismatch => {
+                AuthSessionError::GameMismatch
+            }
+            sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultExpiredTicket => {
+                AuthSessionError::ExpiredTicket
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Reasons Steam rejected the ticket of an auth session
+///
+/// Mapped from the `EAuthSessionResponse` codes steam returns.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AuthSessionValidateError {
+    /// The user isn't connected to steam
+    #[error("user not connected to steam")]
+    UserNotConnectedToSteam,
+    /// The user doesn't own the game or their license expired
+    #[error("no license or license expired")]
+    NoLicenseOrExpired,
+    /// The user is VAC banned for the game
+    #[error("user is VAC banned")]
+    VACBanned,
+    /// The user is logged in elsewhere and the session was dropped
+    #[error("user logged in elsewhere")]
+    LoggedInElseWhere,
+    /// VAC couldn't check the user in time
+    #[error("VAC check timed out")]
+    VACCheckTimedOut,
+    /// The ticket was cancelled by its owner
+    #[error("auth session ticket cancelled")]
+    AuthTicketCanceled,
+    /// The ticket was already used
+    #[error("auth session ticket already used")]
+    AuthTicketInvalidAlreadyUsed,
+    /// The ticket isn't from a user instance currently connected to steam
+    #[error("invalid auth session ticket")]
+    AuthTicketInvalid,
+    /// The user is banned by the publisher
+    #[error("user is banned by the publisher")]
+    PublisherIssuedBan,
+    /// The ticket was created for a different identity
+    #[error("auth session ticket identity mismatch")]
+    AuthTicketNetworkIdentityFailure,
+}
+
+impl From<sys::EAuthSessionResponse> for AuthSessionValidateError {
+    fn from(r: sys::EAuthSessionResponse) -> Self {
+        match r {
+            sys::EAuthSessionResponse::k_EAuthSessionResponseOK => {
+                panic!("EAuthSessionResponse::k_EAuthSessionResponseOK isn't an error")
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseUserNotConnectedToSteam => {
+                AuthSessionValidateError::UserNotConnectedToSteam
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseNoLicenseOrExpired => {
+                AuthSessionValidateError::NoLicenseOrExpired
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseVACBanned => {
+                AuthSessionValidateError::VACBanned
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseLoggedInElseWhere => {
+                AuthSessionValidateError::LoggedInElseWhere
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseVACCheckTimedOut => {
+                AuthSessionValidateError::VACCheckTimedOut
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseAuthTicketCanceled => {
+                AuthSessionValidateError::AuthTicketCanceled
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseAuthTicketInvalidAlreadyUsed => {
+                AuthSessionValidateError::AuthTicketInvalidAlreadyUsed
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseAuthTicketInvalid => {
+                AuthSessionValidateError::AuthTicketInvalid
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponsePublisherIssuedBan => {
+                AuthSessionValidateError::PublisherIssuedBan
+            }
+            sys::EAuthSessionResponse::k_EAuthSessionResponseAuthTicketNetworkIdentityFailure => {
+                AuthSessionValidateError::AuthTicketNetworkIdentityFailure
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Reasons a lobby couldn't be entered
+///
+/// Mapped from the `EChatRoomEnterResponse` codes steam returns.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LobbyEnterError {
+    /// The request failed due to a network or IO error
+    #[error("IO failure")]
+    IOFailure,
+    /// The lobby doesn't exist (anymore)
+    #[error("lobby doesn't exist")]
+    DoesntExist,
+    /// The user isn't allowed to join, e.g. because the lobby is private
+    #[error("not allowed to join the lobby")]
+    NotAllowed,
+    /// The lobby is full
+    #[error("lobby is full")]
+    Full,
+    /// Steam didn't give a more specific reason
+    #[error("unexpected error")]
+    Error,
+    /// The user is banned from the lobby
+    #[error("banned from the lobby")]
+    Banned,
+    /// The user's account is limited and can't join lobbies
+    #[error("limited user account")]
+    Limited,
+    /// The lobby belongs to a clan that has chat disabled
+    #[error("clan chat is disabled")]
+    ClanDisabled,
+    /// The user has a community ban
+    #[error("community ban")]
+    CommunityBan,
+    /// A member of the lobby blocked the user
+    #[error("a member of the lobby blocked you")]
+    MemberBlockedYou,
+    /// The user blocked a member of the lobby
+    #[error("you blocked a member of the lobby")]
+    YouBlockedMember,
+    /// The user tried to join too many lobbies in a short time
+    #[error("rate limit exceeded")]
+    RatelimitExceeded,
+}
+
+impl LobbyEnterError {
+    /// Maps the raw `EChatRoomEnterResponse` of a `LobbyEnter_t`, codes
+    /// unknown to this crate become [`LobbyEnterError::Error`].
+    pub(crate) fn from_response(response: u32) -> LobbyEnterError {
+        use sys::EChatRoomEnterResponse as R;
+        match response {
+            r if r == R::k_EChatRoomEnterResponseDoesntExist as u32 => LobbyEnterError::DoesntExist,
+            r if r == R::k_EChatRoomEnterResponseNotAllowed as u32 => LobbyEnterError::NotAllowed,
+            r if r == R::k_EChatRoomEnterResponseFull as u32 => LobbyEnterError::Full,
+            r if r == R::k_EChatRoomEnterResponseBanned as u32 => LobbyEnterError::Banned,
+            r if r == R::k_EChatRoomEnterResponseLimited as u32 => LobbyEnterError::Limited,
+            r if r == R::k_EChatRoomEnterResponseClanDisabled as u32 => {
+                LobbyEnterError::ClanDisabled
+            }
+            r if r == R::k_EChatRoomEnterResponseCommunityBan as u32 => {
+                LobbyEnterError::CommunityBan
+            }
+            r if r == R::k_EChatRoomEnterResponseMemberBlockedYou as u32 => {
+                LobbyEnterError::MemberBlockedYou
+            }
+            r if r == R::k_EChatRoomEnterResponseYouBlockedMember as u32 => {
+                LobbyEnterError::YouBlockedMember
+            }
+            r if r == R::k_EChatRoomEnterResponseRatelimitExceeded as u32 => {
+                LobbyEnterError::RatelimitExceeded
+            }
+            _ => LobbyEnterError::Error,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("error code could not be converted to rust enum")]
 pub struct InvalidErrorCode;
@@ -748,6 +1059,9 @@ pub enum SteamAPIInitError {
 
     #[error("Steam client appears to be out of date")]
     VersionMismatch(String),
+
+    #[error("The steam_api library doesn't match the SDK this crate was built against")]
+    CallbackMismatch(String),
 }
 
 impl SteamAPIInitError {
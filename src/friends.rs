@@ -1,5 +1,16 @@
 use super::*;
 
+use std::net::Ipv4Addr;
+
+const CALLBACK_BASE_ID: i32 = 300;
+
+/// The maximum number of rich presence keys a user can have set at once
+pub const MAX_RICH_PRESENCE_KEYS: usize = sys::k_cchMaxRichPresenceKeys as usize;
+/// The size of a rich presence key in bytes, including the nul terminator
+pub const MAX_RICH_PRESENCE_KEY_LENGTH: usize = sys::k_cchMaxRichPresenceKeyLength as usize;
+/// The size of a rich presence value in bytes, including the nul terminator
+pub const MAX_RICH_PRESENCE_VALUE_LENGTH: usize = sys::k_cchMaxRichPresenceValueLength as usize;
+
 bitflags! {
     #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(C)]
@@ -22,15 +33,382 @@ bitflags! {
     }
 }
 
+/// A user's online status
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FriendState {
+    Offline,
+    Online,
+    Busy,
+    Away,
+    Snooze,
+    LookingToTrade,
+    LookingToPlay,
+    /// Only reported for the current user, who appears offline to others
+    Invisible,
+}
+
+impl FriendState {
+    fn from_raw(state: sys::EPersonaState) -> FriendState {
+        match state {
+            sys::EPersonaState::k_EPersonaStateOnline => FriendState::Online,
+            sys::EPersonaState::k_EPersonaStateBusy => FriendState::Busy,
+            sys::EPersonaState::k_EPersonaStateAway => FriendState::Away,
+            sys::EPersonaState::k_EPersonaStateSnooze => FriendState::Snooze,
+            sys::EPersonaState::k_EPersonaStateLookingToTrade => FriendState::LookingToTrade,
+            sys::EPersonaState::k_EPersonaStateLookingToPlay => FriendState::LookingToPlay,
+            sys::EPersonaState::k_EPersonaStateInvisible => FriendState::Invisible,
+            _ => FriendState::Offline,
+        }
+    }
+}
+
+/// The relationship between the current user and another user
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FriendRelationship {
+    None,
+    Blocked,
+    /// The other user sent the current user a friend request
+    RequestRecipient,
+    Friend,
+    /// The current user sent the other user a friend request
+    RequestInitiator,
+    Ignored,
+    IgnoredFriend,
+    /// No longer used by Steam
+    Suggested,
+}
+
+impl FriendRelationship {
+    fn from_raw(relationship: sys::EFriendRelationship) -> FriendRelationship {
+        match relationship {
+            sys::EFriendRelationship::k_EFriendRelationshipBlocked => FriendRelationship::Blocked,
+            sys::EFriendRelationship::k_EFriendRelationshipRequestRecipient => {
+                FriendRelationship::RequestRecipient
+            }
+            sys::EFriendRelationship::k_EFriendRelationshipFriend => FriendRelationship::Friend,
+            sys::EFriendRelationship::k_EFriendRelationshipRequestInitiator => {
+                FriendRelationship::RequestInitiator
+            }
+            sys::EFriendRelationship::k_EFriendRelationshipIgnored => FriendRelationship::Ignored,
+            sys::EFriendRelationship::k_EFriendRelationshipIgnoredFriend => {
+                FriendRelationship::IgnoredFriend
+            }
+            sys::EFriendRelationship::k_EFriendRelationshipSuggested_DEPRECATED => {
+                FriendRelationship::Suggested
+            }
+            _ => FriendRelationship::None,
+        }
+    }
+}
+
+/// An id for a group the current user sorted some of their friends into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FriendsGroupId(pub i16);
+
+/// The game a friend is currently playing
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FriendGame {
+    pub game: GameId,
+    /// The address of the game server the friend is on, unspecified if none
+    pub game_address: Ipv4Addr,
+    pub game_port: u16,
+    pub query_port: u16,
+    /// The lobby the friend is in, invalid if none
+    pub lobby: SteamId,
+}
+
+impl FriendGame {
+    unsafe fn from_raw(info: &sys::FriendGameInfo_t) -> FriendGame {
+        FriendGame {
+            game: GameId(info.m_gameID.__bindgen_anon_1.m_ulGameID),
+            game_address: Ipv4Addr::from(info.m_unGameIP),
+            game_port: info.m_usGamePort,
+            query_port: info.m_usQueryPort,
+            lobby: SteamId(info.m_steamIDLobby.m_steamid.m_unAll64Bits),
+        }
+    }
+}
+
+/// Everything needed to render a row in a friends list
+///
+/// Returned by [`Friend::snapshot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FriendSnapshot {
+    pub id: SteamId,
+    pub name: String,
+    pub nickname: Option<String>,
+    pub state: FriendState,
+    pub relationship: FriendRelationship,
+    pub level: u32,
+    pub game: Option<FriendGame>,
+}
+
+/// The sizes Steam provides avatars in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AvatarSize {
+    /// 32x32
+    Small,
+    /// 64x64
+    Medium,
+    /// 184x184
+    Large,
+}
+
+bitflags! {
+    /// What changed about a user in a [`PersonaStateChange`]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[repr(C)]
+    pub struct PersonaChange: i32 {
+        const NAME                 = 0x0001;
+        const STATUS               = 0x0002;
+        const COME_ONLINE          = 0x0004;
+        const GONE_OFFLINE         = 0x0008;
+        const GAME_PLAYED          = 0x0010;
+        const GAME_SERVER          = 0x0020;
+        const AVATAR               = 0x0040;
+        const JOINED_SOURCE        = 0x0080;
+        const LEFT_SOURCE          = 0x0100;
+        const RELATIONSHIP_CHANGED = 0x0200;
+        const NAME_FIRST_SET       = 0x0400;
+        const BROADCAST            = 0x0800;
+        const NICKNAME             = 0x1000;
+        const STEAM_LEVEL          = 0x2000;
+        const RICH_PRESENCE        = 0x4000;
+    }
+}
+
+/// Callback sent whenever a user's persona (name, status, avatar, ...) changes
+///
+/// This is also sent for the current user, e.g. when they rename themselves
+/// in the steam client or through [`Friends::set_persona_name`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use steamworks::*;
+/// # let client = steamworks::Client::init().unwrap();
+/// let me = client.user().steam_id();
+/// let callback_handle = client.register_callback(move |val: PersonaStateChange| {
+///     if val.steam_id == me && val.flags.contains(PersonaChange::NAME) {
+///         // Update the displayed name
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PersonaStateChange {
+    pub steam_id: SteamId,
+    pub flags: PersonaChange,
+}
+
+unsafe impl Callback for PersonaStateChange {
+    const ID: i32 = CALLBACK_BASE_ID + 4;
+    const SIZE: i32 = std::mem::size_of::<sys::PersonaStateChange_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::PersonaStateChange_t);
+        PersonaStateChange {
+            steam_id: SteamId(val.m_ulSteamID),
+            flags: PersonaChange::from_bits_truncate(val.m_nChangeFlags),
+        }
+    }
+}
+
+/// Callback sent when the current user accepts a game invite or clicks
+/// "Join Game" on a friend in the steam overlay or friends list
+///
+/// `connect` is the friend's `connect` rich presence value, or the string
+/// passed to [`Friends::invite_user_to_game`] by the inviting friend.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRichPresenceJoinRequested {
+    pub friend: SteamId,
+    pub connect: String,
+}
+
+unsafe impl Callback for GameRichPresenceJoinRequested {
+    const ID: i32 = CALLBACK_BASE_ID + 37;
+    const SIZE: i32 = std::mem::size_of::<sys::GameRichPresenceJoinRequested_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GameRichPresenceJoinRequested_t);
+        GameRichPresenceJoinRequested {
+            friend: SteamId(val.m_steamIDFriend.m_steamid.m_unAll64Bits),
+            connect: CStr::from_ptr(val.m_rgchConnect.as_ptr())
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+}
+
+/// Callback sent when the steam overlay is opened or closed
+///
+/// Games should pause and stop handling input while the overlay is active.
+/// Only sent when the overlay is enabled, see
+/// [`Utils::is_overlay_enabled`](struct.Utils.html#method.is_overlay_enabled).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameOverlayActivated {
+    pub active: bool,
+}
+
+unsafe impl Callback for GameOverlayActivated {
+    const ID: i32 = CALLBACK_BASE_ID + 31;
+    const SIZE: i32 = std::mem::size_of::<sys::GameOverlayActivated_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GameOverlayActivated_t);
+        GameOverlayActivated {
+            active: val.m_bActive != 0,
+        }
+    }
+}
+
+/// Callback sent when an avatar requested through [`Friend::avatar`] or
+/// [`Friends::my_avatar`] has finished downloading
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AvatarImageLoaded {
+    pub steam_id: SteamId,
+    pub width: u32,
+    pub height: u32,
+}
+
+unsafe impl Callback for AvatarImageLoaded {
+    const ID: i32 = CALLBACK_BASE_ID + 34;
+    const SIZE: i32 = std::mem::size_of::<sys::AvatarImageLoaded_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::AvatarImageLoaded_t);
+        AvatarImageLoaded {
+            steam_id: SteamId(val.m_steamID.m_steamid.m_unAll64Bits),
+            width: val.m_iWide as u32,
+            height: val.m_iTall as u32,
+        }
+    }
+}
+
+/// What else to do when opening a store page with
+/// [`Friends::activate_game_overlay_to_store`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum OverlayToStoreFlag {
+    /// Only show the store page
     None = 0,
+    /// Add the app to the user's cart
     AddToCart = 1,
+    /// Add the app to the user's cart and show the cart
     AddToCartAndShow = 2,
 }
 
+/// How the overlay's web browser is opened
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WebPageMode {
+    /// The browser opens with the rest of the overlay and the user's other
+    /// browser tabs
+    #[default]
+    Default,
+    /// The browser opens on its own and the overlay closes again once the
+    /// user closes it, e.g. for a login flow
+    Modal,
+}
+
+/// The overlay dialogs that can be opened for a user
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OverlayUserDialog {
+    /// The user's steam community profile
+    Profile,
+    /// A chat with the user
+    Chat,
+    /// A trade session with the user
+    JoinTrade,
+    /// The user's stats
+    Stats,
+    /// The user's achievements
+    Achievements,
+    /// Prompts to add the user as a friend
+    FriendAdd,
+    /// Prompts to remove the user as a friend
+    FriendRemove,
+    /// Accepts a friend request from the user
+    FriendRequestAccept,
+    /// Ignores a friend request from the user
+    FriendRequestIgnore,
+}
+
+impl OverlayUserDialog {
+    /// Returns the dialog string steam expects for this dialog.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverlayUserDialog::Profile => "steamid",
+            OverlayUserDialog::Chat => "chat",
+            OverlayUserDialog::JoinTrade => "jointrade",
+            OverlayUserDialog::Stats => "stats",
+            OverlayUserDialog::Achievements => "achievements",
+            OverlayUserDialog::FriendAdd => "friendadd",
+            OverlayUserDialog::FriendRemove => "friendremove",
+            OverlayUserDialog::FriendRequestAccept => "friendrequestaccept",
+            OverlayUserDialog::FriendRequestIgnore => "friendrequestignore",
+        }
+    }
+}
+
+impl std::str::FromStr for OverlayUserDialog {
+    type Err = ();
+
+    fn from_str(dialog: &str) -> Result<Self, ()> {
+        Ok(match dialog {
+            "steamid" => OverlayUserDialog::Profile,
+            "chat" => OverlayUserDialog::Chat,
+            "jointrade" => OverlayUserDialog::JoinTrade,
+            "stats" => OverlayUserDialog::Stats,
+            "achievements" => OverlayUserDialog::Achievements,
+            "friendadd" => OverlayUserDialog::FriendAdd,
+            "friendremove" => OverlayUserDialog::FriendRemove,
+            "friendrequestaccept" => OverlayUserDialog::FriendRequestAccept,
+            "friendrequestignore" => OverlayUserDialog::FriendRequestIgnore,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// The community profile items a user currently has equipped.
+///
+/// Returned by [`Friends::request_equipped_profile_items`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EquippedProfileItems {
+    pub steam_id: SteamId,
+    pub has_animated_avatar: bool,
+    pub has_avatar_frame: bool,
+    pub has_profile_modifier: bool,
+    pub has_profile_background: bool,
+    pub has_mini_profile_background: bool,
+}
+
+/// The images of an equipped community profile item.
+///
+/// Steam doesn't provide image handles for profile items, only URLs
+/// to the images on its CDN. Animated items are served as animated
+/// images, so fetching and decoding the frames is left to the caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProfileItemImage {
+    pub small_url: Option<String>,
+    pub large_url: Option<String>,
+}
+
 /// Access to the steam friends interface
 pub struct Friends<Manager> {
     pub(crate) friends: *mut sys::ISteamFriends,
+    pub(crate) user: *mut sys::ISteamUser,
     pub(crate) inner: Arc<Inner<Manager>>,
 }
 
@@ -44,6 +422,46 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Returns the online status of the current user
+    pub fn personal_state(&self) -> FriendState {
+        unsafe { FriendState::from_raw(sys::SteamAPI_ISteamFriends_GetPersonaState(self.friends)) }
+    }
+
+    /// Changes the current user's persona name.
+    ///
+    /// A [`PersonaStateChange`] for the current user is sent as well once
+    /// the name has changed. `cb` receives [`SteamError::InvalidName`] right
+    /// away if `name` contains a nul byte.
+    pub fn set_persona_name<F>(&self, name: &str, cb: F)
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return cb(Err(SteamError::InvalidName)),
+        };
+        unsafe {
+            let api_call =
+                sys::SteamAPI_ISteamFriends_SetPersonaName(self.friends, name.as_ptr() as *const _);
+            register_call_result::<sys::SetPersonaNameResponse_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 47,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_bSuccess {
+                        Ok(())
+                    } else if v.m_result != sys::EResult::k_EResultOK {
+                        Err(v.m_result.into())
+                    } else {
+                        Err(SteamError::Generic)
+                    })
+                },
+            );
+        }
+    }
+
     pub fn get_friends(&self, flags: FriendFlags) -> Vec<Friend<Manager>> {
         unsafe {
             let count = sys::SteamAPI_ISteamFriends_GetFriendCount(self.friends, flags.bits() as _);
@@ -64,6 +482,69 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Returns the friends groups ("tags") the current user created.
+    pub fn get_friends_groups(&self) -> Vec<FriendsGroupId> {
+        unsafe {
+            let count = sys::SteamAPI_ISteamFriends_GetFriendsGroupCount(self.friends);
+            (0..count)
+                .map(|idx| {
+                    FriendsGroupId(sys::SteamAPI_ISteamFriends_GetFriendsGroupIDByIndex(
+                        self.friends,
+                        idx,
+                    ))
+                })
+                .filter(|id| id.0 != sys::k_FriendsGroupID_Invalid)
+                .collect()
+        }
+    }
+
+    /// Returns the name of a friends group, `None` if it doesn't exist.
+    pub fn friends_group_name(&self, id: FriendsGroupId) -> Option<String> {
+        unsafe {
+            let name = sys::SteamAPI_ISteamFriends_GetFriendsGroupName(self.friends, id.0);
+            if name.is_null() {
+                return None;
+            }
+            Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    }
+
+    /// Returns the members of a friends group.
+    pub fn friends_group_members(&self, id: FriendsGroupId) -> Vec<SteamId> {
+        unsafe {
+            let count = sys::SteamAPI_ISteamFriends_GetFriendsGroupMembersCount(self.friends, id.0);
+            if count <= 0 {
+                return Vec::new();
+            }
+            let mut members = vec![
+                sys::CSteamID {
+                    m_steamid: sys::CSteamID_SteamID_t { m_unAll64Bits: 0 },
+                };
+                count as usize
+            ];
+            sys::SteamAPI_ISteamFriends_GetFriendsGroupMembersList(
+                self.friends,
+                id.0,
+                members.as_mut_ptr(),
+                count,
+            );
+            members
+                .into_iter()
+                .map(|id| SteamId(id.m_steamid.m_unAll64Bits))
+                .collect()
+        }
+    }
+
+    /// Returns the current user's avatar in RGBA format
+    ///
+    /// Returns `None` if the user has no avatar or it hasn't been downloaded yet.
+    pub fn my_avatar(&self, size: AvatarSize) -> Option<Image> {
+        unsafe {
+            let me = sys::SteamAPI_ISteamUser_GetSteamID(self.user);
+            avatar(self.friends, me, size)
+        }
+    }
+
     pub fn get_friend(&self, friend: SteamId) -> Friend<Manager> {
         Friend {
             id: friend,
@@ -78,6 +559,230 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Requests the number of users following `user`.
+    pub fn get_follower_count<F>(&self, user: SteamId, cb: F)
+    where
+        F: FnOnce(Result<i32, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamFriends_GetFollowerCount(self.friends, user.0);
+            register_call_result::<sys::FriendsGetFollowerCount_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 44,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(v.m_nCount)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Requests whether the current user is following `user`.
+    pub fn is_following<F>(&self, user: SteamId, cb: F)
+    where
+        F: FnOnce(Result<bool, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamFriends_IsFollowing(self.friends, user.0);
+            register_call_result::<sys::FriendsIsFollowing_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 45,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(v.m_bIsFollowing)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Requests every user the current user is following.
+    ///
+    /// Steam returns the list in pages, which are requested one after the
+    /// other. `cb` receives the complete list once the last page arrived.
+    pub fn enumerate_following_list<F>(&self, cb: F)
+    where
+        Manager: Send + Sync + 'static,
+        F: FnOnce(Result<Vec<SteamId>, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            enumerate_following_page(&self.inner, Vec::new(), cb);
+        }
+    }
+
+    /// Requests the community profile items the given user has equipped.
+    ///
+    /// Must complete before [`Friend::avatar_frame`] and
+    /// [`Friend::animated_avatar`] return anything for that user.
+    pub fn request_equipped_profile_items<F>(&self, user: SteamId, cb: F)
+    where
+        F: FnOnce(Result<EquippedProfileItems, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call =
+                sys::SteamAPI_ISteamFriends_RequestEquippedProfileItems(self.friends, user.0);
+            register_call_result::<sys::EquippedProfileItems_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 50,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(EquippedProfileItems {
+                            steam_id: SteamId(v.m_steamID.m_steamid.m_unAll64Bits),
+                            has_animated_avatar: v.m_bHasAnimatedAvatar,
+                            has_avatar_frame: v.m_bHasAvatarFrame,
+                            has_profile_modifier: v.m_bHasProfileModifier,
+                            has_profile_background: v.m_bHasProfileBackground,
+                            has_mini_profile_background: v.m_bHasMiniProfileBackground,
+                        })
+                    })
+                },
+            );
+        }
+    }
+
+    /// Requests the officer list of a clan (steam group).
+    ///
+    /// `cb` receives the number of officers. Must complete before
+    /// [`clan_owner`](#method.clan_owner),
+    /// [`clan_officer_count`](#method.clan_officer_count) and
+    /// [`clan_officer_by_index`](#method.clan_officer_by_index) return
+    /// anything for that clan.
+    pub fn request_clan_officer_list<F>(&self, clan: SteamId, cb: F)
+    where
+        F: FnOnce(Result<i32, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamFriends_RequestClanOfficerList(self.friends, clan.0);
+            register_call_result::<sys::ClanOfficerListResponse_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 35,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_bSuccess == 0 {
+                        Err(SteamError::Generic)
+                    } else {
+                        Ok(v.m_cOfficers)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Returns the owner of a clan.
+    ///
+    /// Requires [`request_clan_officer_list`](#method.request_clan_officer_list)
+    /// to have completed for the clan.
+    pub fn clan_owner(&self, clan: SteamId) -> SteamId {
+        unsafe {
+            SteamId(sys::SteamAPI_ISteamFriends_GetClanOwner(
+                self.friends,
+                clan.0,
+            ))
+        }
+    }
+
+    /// Returns the number of officers of a clan, including the owner.
+    ///
+    /// Requires [`request_clan_officer_list`](#method.request_clan_officer_list)
+    /// to have completed for the clan.
+    pub fn clan_officer_count(&self, clan: SteamId) -> i32 {
+        unsafe { sys::SteamAPI_ISteamFriends_GetClanOfficerCount(self.friends, clan.0) }
+    }
+
+    /// Returns the officer of a clan at `index`.
+    ///
+    /// `index` should be less than [`clan_officer_count`](#method.clan_officer_count).
+    pub fn clan_officer_by_index(&self, clan: SteamId, index: i32) -> SteamId {
+        unsafe {
+            SteamId(sys::SteamAPI_ISteamFriends_GetClanOfficerByIndex(
+                self.friends,
+                clan.0,
+                index,
+            ))
+        }
+    }
+
+    /// Returns the clans (steam groups) the current user is a member of.
+    pub fn get_clans(&self) -> Vec<Clan<Manager>> {
+        unsafe {
+            let count = sys::SteamAPI_ISteamFriends_GetClanCount(self.friends);
+            (0..count)
+                .map(|idx| Clan {
+                    id: SteamId(sys::SteamAPI_ISteamFriends_GetClanByIndex(
+                        self.friends,
+                        idx,
+                    )),
+                    friends: self.friends,
+                    _inner: self.inner.clone(),
+                })
+                .collect()
+        }
+    }
+
+    /// Downloads how many members of each clan are online, in game and
+    /// chatting.
+    ///
+    /// The counts of clans the current user isn't a member of can be
+    /// requested as well. Afterwards [`Clan::activity_counts`] returns the
+    /// downloaded counts too.
+    pub fn download_clan_activity_counts<F>(&self, clans: &[SteamId], cb: F)
+    where
+        F: FnOnce(Result<Vec<ClanActivityCounts>, SteamError>) + 'static + Send,
+    {
+        let clans = clans.to_vec();
+        let mut ids: Vec<sys::CSteamID> = clans
+            .iter()
+            .map(|clan| sys::CSteamID {
+                m_steamid: sys::CSteamID_SteamID_t {
+                    m_unAll64Bits: clan.0,
+                },
+            })
+            .collect();
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamFriends_DownloadClanActivityCounts(
+                self.friends,
+                ids.as_mut_ptr(),
+                ids.len() as i32,
+            );
+            register_call_result::<sys::DownloadClanActivityCountsResult_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 41,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if !v.m_bSuccess {
+                        Err(SteamError::Generic)
+                    } else {
+                        let friends = sys::SteamAPI_SteamFriends_v017();
+                        Ok(clans
+                            .into_iter()
+                            .filter_map(|clan| clan_activity_counts(friends, clan))
+                            .collect())
+                    })
+                },
+            );
+        }
+    }
+
     pub fn activate_game_overlay(&self, dialog: &str) {
         let dialog = CString::new(dialog).unwrap();
         unsafe {
@@ -90,17 +795,90 @@ impl<Manager> Friends<Manager> {
 
     // I don't know why these are part of friends either
     pub fn activate_game_overlay_to_web_page(&self, url: &str) {
+        self.activate_game_overlay_to_web_page_with_mode(url, WebPageMode::Default);
+    }
+
+    /// Opens the overlay's web browser to `url`.
+    ///
+    /// See [`WebPageMode`] for how the browser behaves.
+    pub fn activate_game_overlay_to_web_page_with_mode(&self, url: &str, mode: WebPageMode) {
+        let mode = match mode {
+            WebPageMode::Default => {
+                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Default
+            }
+            WebPageMode::Modal => {
+                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Modal
+            }
+        };
         unsafe {
             let url = CString::new(url).unwrap();
             sys::SteamAPI_ISteamFriends_ActivateGameOverlayToWebPage(
                 self.friends,
                 url.as_ptr() as *const _,
-                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Default,
+                mode,
+            );
+        }
+    }
+
+    /// Opens the overlay's invite dialog for `lobby`.
+    ///
+    /// Lobbies are identified by their steam id, as returned by
+    /// [`Matchmaking::create_lobby`](struct.Matchmaking.html#method.create_lobby).
+    pub fn activate_game_overlay_invite_dialog(&self, lobby: SteamId) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayInviteDialog(self.friends, lobby.0);
+        }
+    }
+
+    /// Opens the overlay's Remote Play Together invite dialog for `lobby`.
+    pub fn activate_remote_play_together_invite_dialog(&self, lobby: SteamId) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayRemotePlayTogetherInviteDialog(
+                self.friends,
+                lobby.0,
             );
         }
     }
 
+    /// Opens the overlay to the store page of `app_id`.
+    ///
+    /// `flag` controls whether the app is also added to the user's cart.
+    pub fn activate_game_overlay_to_store(&self, app_id: AppId, flag: OverlayToStoreFlag) {
+        let flag = match flag {
+            OverlayToStoreFlag::None => sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_None,
+            OverlayToStoreFlag::AddToCart => {
+                sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_AddToCart
+            }
+            OverlayToStoreFlag::AddToCartAndShow => {
+                sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_AddToCartAndShow
+            }
+        };
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayToStore(self.friends, app_id.0, flag);
+        }
+    }
+
+    /// Opens the overlay to the store page of a DLC, adding it to the
+    /// user's cart and showing the cart.
+    ///
+    /// This is the usual "Buy DLC" button, see
+    /// [`activate_game_overlay_to_store`](#method.activate_game_overlay_to_store)
+    /// for the other store options.
+    pub fn open_dlc_store(&self, app_id: AppId) {
+        self.activate_game_overlay_to_store(app_id, OverlayToStoreFlag::AddToCartAndShow);
+    }
+
+    /// Opens the overlay to a dialog for `user`.
+    ///
+    /// `dialog` must be one of the strings listed by [`OverlayUserDialog`],
+    /// which is checked in debug builds. Prefer
+    /// [`activate_game_overlay_to_user_dialog`](#method.activate_game_overlay_to_user_dialog).
     pub fn activate_game_overlay_to_user(&self, dialog: &str, user: SteamId) {
+        debug_assert!(
+            dialog.parse::<OverlayUserDialog>().is_ok(),
+            "unknown overlay user dialog {:?}",
+            dialog
+        );
         let dialog = CString::new(dialog).unwrap();
         unsafe {
             sys::SteamAPI_ISteamFriends_ActivateGameOverlayToUser(
@@ -111,26 +889,143 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Opens the overlay to a dialog for `user`.
+    pub fn activate_game_overlay_to_user_dialog(&self, dialog: OverlayUserDialog, user: SteamId) {
+        self.activate_game_overlay_to_user(dialog.as_str(), user);
+    }
+
     /// Set rich presence for the user. Unsets the rich presence if `value` is None or empty.
     /// See [Steam API](https://partner.steamgames.com/doc/api/ISteamFriends#SetRichPresence)
+    ///
+    /// Returns `false` if the update was refused. Use
+    /// [`try_set_rich_presence`](#method.try_set_rich_presence) to find out why.
     pub fn set_rich_presence(&self, key: &str, value: Option<&str>) -> bool {
+        self.try_set_rich_presence(key, value).is_ok()
+    }
+
+    /// Set rich presence for the user, checking it against Steam's limits first.
+    ///
+    /// Steam silently drops keys or values over the documented limits, so
+    /// these are checked here instead. See
+    /// [`set_rich_presence`](#method.set_rich_presence).
+    pub fn try_set_rich_presence(
+        &self,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), RichPresenceError> {
+        let value = value.unwrap_or_default();
+        if key.len() >= MAX_RICH_PRESENCE_KEY_LENGTH {
+            return Err(RichPresenceError::KeyTooLong);
+        }
+        if value.len() >= MAX_RICH_PRESENCE_VALUE_LENGTH {
+            return Err(RichPresenceError::ValueTooLong);
+        }
+        let key = CString::new(key).map_err(|_| RichPresenceError::NulByte)?;
+        let value = CString::new(value).map_err(|_| RichPresenceError::NulByte)?;
         unsafe {
-            // Unwraps are infallible because Rust strs cannot contain null bytes
-            let key = CString::new(key).unwrap();
-            let value = CString::new(value.unwrap_or_default()).unwrap();
-            sys::SteamAPI_ISteamFriends_SetRichPresence(
+            if !value.is_empty() && !self.has_own_rich_presence_key(&key) {
+                let me = sys::SteamAPI_ISteamUser_GetSteamID(self.user);
+                let count =
+                    sys::SteamAPI_ISteamFriends_GetFriendRichPresenceKeyCount(self.friends, me);
+                if count.max(0) as usize >= MAX_RICH_PRESENCE_KEYS {
+                    return Err(RichPresenceError::TooManyKeys);
+                }
+            }
+            if sys::SteamAPI_ISteamFriends_SetRichPresence(
                 self.friends,
                 key.as_ptr() as *const _,
                 value.as_ptr() as *const _,
-            )
+            ) {
+                Ok(())
+            } else {
+                Err(RichPresenceError::Rejected)
+            }
+        }
+    }
+
+    fn has_own_rich_presence_key(&self, key: &CStr) -> bool {
+        unsafe {
+            let me = sys::SteamAPI_ISteamUser_GetSteamID(self.user);
+            let value = sys::SteamAPI_ISteamFriends_GetFriendRichPresence(
+                self.friends,
+                me,
+                key.as_ptr() as *const _,
+            );
+            !value.is_null() && !CStr::from_ptr(value).is_empty()
         }
     }
+
     /// Clears all of the current user's Rich Presence key/values.
     pub fn clear_rich_presence(&self) {
         unsafe {
             sys::SteamAPI_ISteamFriends_ClearRichPresence(self.friends);
         }
     }
+
+    /// Marks `user` as having played with the current user.
+    ///
+    /// Shows them in the overlay's "recently played with" list, so call this
+    /// when a multiplayer session with them starts.
+    pub fn set_played_with(&self, user: SteamId) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_SetPlayedWith(self.friends, user.0);
+        }
+    }
+
+    /// Returns the users the current user recently played with.
+    pub fn get_coplay_friends(&self) -> Vec<SteamId> {
+        unsafe {
+            let count = sys::SteamAPI_ISteamFriends_GetCoplayFriendCount(self.friends);
+            (0..count)
+                .map(|idx| {
+                    SteamId(sys::SteamAPI_ISteamFriends_GetCoplayFriend(
+                        self.friends,
+                        idx,
+                    ))
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the game the current user last played with `user` in, `None`
+    /// if they haven't played together.
+    pub fn get_friend_coplay_game(&self, user: SteamId) -> Option<GameId> {
+        unsafe {
+            match sys::SteamAPI_ISteamFriends_GetFriendCoplayGame(self.friends, user.0) {
+                0 => None,
+                app_id => Some(GameId(app_id as u64)),
+            }
+        }
+    }
+
+    /// Returns when the current user last played with `user`, as a Unix
+    /// timestamp in seconds. `None` if they haven't played together.
+    pub fn get_friend_coplay_time(&self, user: SteamId) -> Option<u32> {
+        unsafe {
+            match sys::SteamAPI_ISteamFriends_GetFriendCoplayTime(self.friends, user.0) {
+                time if time <= 0 => None,
+                time => Some(time as u32),
+            }
+        }
+    }
+
+    /// Invites a friend to the current game.
+    ///
+    /// `connect` is passed to the game on the friend's side, like the
+    /// `connect` rich presence key. Returns `false` if the invite couldn't be
+    /// sent, or `connect` is too long for steam or contains a nul byte.
+    pub fn invite_user_to_game(&self, user: SteamId, connect: &str) -> bool {
+        if connect.len() >= MAX_RICH_PRESENCE_VALUE_LENGTH {
+            return false;
+        }
+        let connect = match CString::new(connect) {
+            Ok(connect) => connect,
+            Err(_) => return false,
+        };
+        unsafe {
+            sys::SteamAPI_ISteamFriends_InviteUserToGame(self.friends, user.0, connect.as_ptr())
+        }
+    }
 }
 
 pub struct Friend<Manager> {
@@ -170,73 +1065,151 @@ impl<Manager> Friend<Manager> {
         }
     }
 
-    /// Returns a small (32x32) avatar for the user in RGBA format
-    pub fn small_avatar(&self) -> Option<Vec<u8>> {
+    /// Returns the user's online status.
+    pub fn state(&self) -> FriendState {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
-            let img = sys::SteamAPI_ISteamFriends_GetSmallFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
-                return None;
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 32);
-            assert_eq!(height, 32);
-            let mut dest = vec![0; 32 * 32 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 32 * 32 * 4) {
-                return None;
-            }
-            Some(dest)
+            FriendState::from_raw(sys::SteamAPI_ISteamFriends_GetFriendPersonaState(
+                self.friends,
+                self.id.0,
+            ))
         }
     }
 
-    /// Returns a medium (64x64) avatar for the user in RGBA format
-    pub fn medium_avatar(&self) -> Option<Vec<u8>> {
+    /// Returns the relationship between the current user and this user.
+    ///
+    /// Unlike [`has_friend`](#method.has_friend) this is a single value, so "are we friends"
+    /// is a comparison with [`FriendRelationship::Friend`].
+    pub fn relationship(&self) -> FriendRelationship {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
-            let img = sys::SteamAPI_ISteamFriends_GetMediumFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
-                return None;
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 64);
-            assert_eq!(height, 64);
-            let mut dest = vec![0; 64 * 64 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 64 * 64 * 4) {
-                return None;
+            FriendRelationship::from_raw(sys::SteamAPI_ISteamFriends_GetFriendRelationship(
+                self.friends,
+                self.id.0,
+            ))
+        }
+    }
+
+    /// Returns the user's steam level, like [`User::level`](struct.User.html#method.level)
+    /// does for the current user.
+    ///
+    /// Returns `0` if the level isn't known yet, see
+    /// [`Friends::request_user_information`]. A [`PersonaStateChange`] with
+    /// [`PersonaChange::STEAM_LEVEL`] is sent once it is.
+    pub fn level(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamFriends_GetFriendSteamLevel(self.friends, self.id.0) as u32 }
+    }
+
+    /// Returns the game the user is currently playing, if any.
+    ///
+    /// Returns `None` if the user isn't in a game. The game id is the same one
+    /// reported by the [`UserStatsReceived`](struct.UserStatsReceived.html) callback,
+    /// so it can be compared with the current game.
+    pub fn game_played(&self) -> Option<FriendGame> {
+        unsafe {
+            let mut info: sys::FriendGameInfo_t = std::mem::zeroed();
+            if sys::SteamAPI_ISteamFriends_GetFriendGamePlayed(self.friends, self.id.0, &mut info) {
+                Some(FriendGame::from_raw(&info))
+            } else {
+                None
             }
-            Some(dest)
         }
     }
 
+    /// Returns everything needed to show the user in a friends list at once.
+    ///
+    /// Nothing is cached, each call reads the current values from Steam.
+    pub fn snapshot(&self) -> FriendSnapshot {
+        FriendSnapshot {
+            id: self.id,
+            name: self.name(),
+            nickname: self.nick_name(),
+            state: self.state(),
+            relationship: self.relationship(),
+            level: self.level(),
+            game: self.game_played(),
+        }
+    }
+
+    /// Returns the user's avatar in RGBA format, in whatever dimensions steam
+    /// reports for it.
+    ///
+    /// Returns `None` if the user has no avatar or it is still being
+    /// downloaded. In the latter case an [`AvatarImageLoaded`] callback is sent
+    /// once it is ready and this can be called again.
+    pub fn avatar(&self, size: AvatarSize) -> Option<Image> {
+        unsafe { avatar(self.friends, self.id.0, size) }
+    }
+
+    /// Returns a small (32x32) avatar for the user in RGBA format
+    ///
+    /// See [`avatar`](#method.avatar), which also returns the dimensions.
+    pub fn small_avatar(&self) -> Option<Vec<u8>> {
+        self.avatar(AvatarSize::Small).map(|image| image.data)
+    }
+
+    /// Returns a medium (64x64) avatar for the user in RGBA format
+    ///
+    /// See [`avatar`](#method.avatar), which also returns the dimensions.
+    pub fn medium_avatar(&self) -> Option<Vec<u8>> {
+        self.avatar(AvatarSize::Medium).map(|image| image.data)
+    }
+
     /// Returns a large (184x184) avatar for the user in RGBA format
+    ///
+    /// See [`avatar`](#method.avatar), which also returns the dimensions.
     pub fn large_avatar(&self) -> Option<Vec<u8>> {
+        self.avatar(AvatarSize::Large).map(|image| image.data)
+    }
+
+    /// Returns the images of the user's equipped avatar frame, if any.
+    ///
+    /// Requires [`Friends::request_equipped_profile_items`] to have completed
+    /// for this user.
+    pub fn avatar_frame(&self) -> Option<ProfileItemImage> {
+        self.profile_item_image(
+            sys::ECommunityProfileItemType::k_ECommunityProfileItemType_AvatarFrame,
+        )
+    }
+
+    /// Returns the images of the user's equipped animated avatar, if any.
+    ///
+    /// Requires [`Friends::request_equipped_profile_items`] to have completed
+    /// for this user.
+    pub fn animated_avatar(&self) -> Option<ProfileItemImage> {
+        self.profile_item_image(
+            sys::ECommunityProfileItemType::k_ECommunityProfileItemType_AnimatedAvatar,
+        )
+    }
+
+    fn profile_item_image(&self, item: sys::ECommunityProfileItemType) -> Option<ProfileItemImage> {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
-            let img = sys::SteamAPI_ISteamFriends_GetLargeFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
+            if !sys::SteamAPI_ISteamFriends_BHasEquippedProfileItem(self.friends, self.id.0, item) {
                 return None;
             }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 184);
-            assert_eq!(height, 184);
-            let mut dest = vec![0; 184 * 184 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 184 * 184 * 4)
-            {
-                return None;
-            }
-            Some(dest)
+            let url = |prop| {
+                let url = sys::SteamAPI_ISteamFriends_GetProfileItemPropertyString(
+                    self.friends,
+                    self.id.0,
+                    item,
+                    prop,
+                );
+                if url.is_null() {
+                    return None;
+                }
+                let url = CStr::from_ptr(url);
+                if url.is_empty() {
+                    None
+                } else {
+                    Some(url.to_string_lossy().into_owned())
+                }
+            };
+            Some(ProfileItemImage {
+                small_url: url(
+                    sys::ECommunityProfileItemProperty::k_ECommunityProfileItemProperty_ImageSmall,
+                ),
+                large_url: url(
+                    sys::ECommunityProfileItemProperty::k_ECommunityProfileItemProperty_ImageLarge,
+                ),
+            })
         }
     }
 
@@ -245,3 +1218,139 @@ impl<Manager> Friend<Manager> {
         unsafe { sys::SteamAPI_ISteamFriends_HasFriend(self.friends, self.id.0, flags.bits() as _) }
     }
 }
+
+/// A clan (steam group)
+pub struct Clan<Manager> {
+    id: SteamId,
+    friends: *mut sys::ISteamFriends,
+    _inner: Arc<Inner<Manager>>,
+}
+
+impl<Manager> Debug for Clan<Manager> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Clan({:?})", self.id)
+    }
+}
+
+/// How many members of a clan are active
+///
+/// Returned by [`Friends::download_clan_activity_counts`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClanActivityCounts {
+    pub clan: SteamId,
+    pub online: i32,
+    pub in_game: i32,
+    pub chatting: i32,
+}
+
+impl<Manager> Clan<Manager> {
+    pub fn id(&self) -> SteamId {
+        self.id
+    }
+
+    /// Returns the name of the clan.
+    pub fn name(&self) -> String {
+        unsafe {
+            let name = sys::SteamAPI_ISteamFriends_GetClanName(self.friends, self.id.0);
+            let name = CStr::from_ptr(name);
+            name.to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns the abbreviation of the clan shown next to member names.
+    pub fn tag(&self) -> String {
+        unsafe {
+            let tag = sys::SteamAPI_ISteamFriends_GetClanTag(self.friends, self.id.0);
+            let tag = CStr::from_ptr(tag);
+            tag.to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns how many members of the clan are active.
+    ///
+    /// Only known for clans the current user is a member of, or whose counts
+    /// were downloaded with [`Friends::download_clan_activity_counts`].
+    pub fn activity_counts(&self) -> Option<ClanActivityCounts> {
+        unsafe { clan_activity_counts(self.friends, self.id) }
+    }
+}
+
+unsafe fn avatar(friends: *mut sys::ISteamFriends, user: u64, size: AvatarSize) -> Option<Image> {
+    let img = match size {
+        AvatarSize::Small => sys::SteamAPI_ISteamFriends_GetSmallFriendAvatar(friends, user),
+        AvatarSize::Medium => sys::SteamAPI_ISteamFriends_GetMediumFriendAvatar(friends, user),
+        AvatarSize::Large => sys::SteamAPI_ISteamFriends_GetLargeFriendAvatar(friends, user),
+    };
+    Image::from_handle(img)
+}
+
+/// Requests the page of the following list after `following` and hands the
+/// result on to the next page until the list is complete
+unsafe fn enumerate_following_page<Manager, F>(
+    inner: &Arc<Inner<Manager>>,
+    mut following: Vec<SteamId>,
+    cb: F,
+) where
+    Manager: Send + Sync + 'static,
+    F: FnOnce(Result<Vec<SteamId>, SteamError>) + 'static + Send,
+{
+    let api_call = sys::SteamAPI_ISteamFriends_EnumerateFollowingList(
+        sys::SteamAPI_SteamFriends_v017(),
+        following.len() as u32,
+    );
+    // Weak so a page that never arrives doesn't keep the client alive
+    let weak = Arc::downgrade(inner);
+    register_call_result::<sys::FriendsEnumerateFollowingList_t, _, _>(
+        inner,
+        api_call,
+        CALLBACK_BASE_ID + 46,
+        move |v, io_error| {
+            if io_error {
+                cb(Err(SteamError::IOFailure));
+                return;
+            }
+            if v.m_eResult != sys::EResult::k_EResultOK {
+                cb(Err(v.m_eResult.into()));
+                return;
+            }
+            let returned = (v.m_nResultsReturned.max(0) as usize).min(v.m_rgSteamID.len());
+            following.extend(
+                v.m_rgSteamID[..returned]
+                    .iter()
+                    .map(|id| SteamId(id.m_steamid.m_unAll64Bits)),
+            );
+            if returned == 0 || following.len() >= v.m_nTotalResultCount.max(0) as usize {
+                cb(Ok(following));
+                return;
+            }
+            match weak.upgrade() {
+                Some(inner) => enumerate_following_page(&inner, following, cb),
+                None => cb(Err(SteamError::Generic)),
+            }
+        },
+    );
+}
+
+unsafe fn clan_activity_counts(
+    friends: *mut sys::ISteamFriends,
+    clan: SteamId,
+) -> Option<ClanActivityCounts> {
+    let mut counts = ClanActivityCounts {
+        clan,
+        online: 0,
+        in_game: 0,
+        chatting: 0,
+    };
+    if sys::SteamAPI_ISteamFriends_GetClanActivityCounts(
+        friends,
+        clan.0,
+        &mut counts.online,
+        &mut counts.in_game,
+        &mut counts.chatting,
+    ) {
+        Some(counts)
+    } else {
+        None
+    }
+}
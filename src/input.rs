@@ -0,0 +1,192 @@
+use super::*;
+
+/// Access to the steam input interface
+pub struct Input<Manager> {
+    pub(crate) input: *mut sys::ISteamInput,
+    pub(crate) _inner: Arc<Inner<Manager>>,
+}
+
+/// What kind of physical input is driving an analog action
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnalogSourceMode {
+    /// A stick, or a trackpad/mouse emulating one. Values are in `-1.0..=1.0`.
+    Joystick,
+    /// A mouse or something emulating one. Values are deltas, not positions.
+    Mouse,
+    /// An analog trigger. Only `x` is used, in `0.0..=1.0`.
+    Trigger,
+    /// Any other mode, e.g. a dpad or a radial menu
+    Other,
+}
+
+/// The current state of an analog action
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnalogActionData {
+    pub mode: AnalogSourceMode,
+    pub x: f32,
+    pub y: f32,
+    /// Whether the action is bound in the active action set
+    pub active: bool,
+}
+
+impl<Manager> Input<Manager> {
+    /// Initializes steam input.
+    ///
+    /// Steam would otherwise update the input state from `SteamAPI_RunCallbacks`,
+    /// which isn't called as this crate dispatches callbacks manually. Call
+    /// [`run_frame`](#method.run_frame) every frame either way.
+    pub fn init(&self, explicitly_call_run_frame: bool) -> bool {
+        unsafe { sys::SteamAPI_ISteamInput_Init(self.input, explicitly_call_run_frame) }
+    }
+
+    /// Shuts down steam input.
+    pub fn shutdown(&self) {
+        unsafe {
+            sys::SteamAPI_ISteamInput_Shutdown(self.input);
+        }
+    }
+
+    /// Synchronizes the input state with the controllers.
+    pub fn run_frame(&self) {
+        unsafe { sys::SteamAPI_ISteamInput_RunFrame(self.input, false) }
+    }
+
+    /// Returns the handles of the currently connected controllers.
+    pub fn get_connected_controllers(&self) -> Vec<sys::InputHandle_t> {
+        unsafe {
+            let mut handles = vec![0; sys::STEAM_INPUT_MAX_COUNT as usize];
+            let count =
+                sys::SteamAPI_ISteamInput_GetConnectedControllers(self.input, handles.as_mut_ptr());
+            handles.truncate(count.max(0) as usize);
+            handles
+        }
+    }
+
+    /// Returns the handle of an action set by its name in the action manifest.
+    ///
+    /// Returns the invalid handle `0` if there is no action set with that name.
+    pub fn get_action_set_handle(&self, name: &str) -> sys::InputActionSetHandle_t {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return 0,
+        };
+        unsafe { sys::SteamAPI_ISteamInput_GetActionSetHandle(self.input, name.as_ptr()) }
+    }
+
+    /// Makes `action_set` the active action set of `controller`.
+    pub fn activate_action_set(
+        &self,
+        controller: sys::InputHandle_t,
+        action_set: sys::InputActionSetHandle_t,
+    ) {
+        unsafe { sys::SteamAPI_ISteamInput_ActivateActionSet(self.input, controller, action_set) }
+    }
+
+    /// Returns the handle of an analog action by its name in the action manifest.
+    ///
+    /// Returns the invalid handle `0` if there is no analog action with that name.
+    pub fn get_analog_action_handle(&self, name: &str) -> sys::InputAnalogActionHandle_t {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return 0,
+        };
+        unsafe { sys::SteamAPI_ISteamInput_GetAnalogActionHandle(self.input, name.as_ptr()) }
+    }
+
+    /// Returns the current state of an analog action on `controller`.
+    pub fn get_analog_action_data(
+        &self,
+        controller: sys::InputHandle_t,
+        action: sys::InputAnalogActionHandle_t,
+    ) -> AnalogActionData {
+        unsafe {
+            let data =
+                sys::SteamAPI_ISteamInput_GetAnalogActionData(self.input, controller, action);
+            AnalogActionData {
+                mode: match data.eMode {
+                    sys::EInputSourceMode::k_EInputSourceMode_JoystickMove
+                    | sys::EInputSourceMode::k_EInputSourceMode_JoystickCamera
+                    | sys::EInputSourceMode::k_EInputSourceMode_MouseJoystick => {
+                        AnalogSourceMode::Joystick
+                    }
+                    sys::EInputSourceMode::k_EInputSourceMode_AbsoluteMouse
+                    | sys::EInputSourceMode::k_EInputSourceMode_RelativeMouse
+                    | sys::EInputSourceMode::k_EInputSourceMode_JoystickMouse
+                    | sys::EInputSourceMode::k_EInputSourceMode_MouseRegion
+                    | sys::EInputSourceMode::k_EInputSourceMode_ScrollWheel => {
+                        AnalogSourceMode::Mouse
+                    }
+                    sys::EInputSourceMode::k_EInputSourceMode_Trigger => AnalogSourceMode::Trigger,
+                    _ => AnalogSourceMode::Other,
+                },
+                x: data.x,
+                y: data.y,
+                active: data.bActive,
+            }
+        }
+    }
+
+    /// Returns the value of an analog action with a radial deadzone applied.
+    ///
+    /// Returns `None` if the action isn't active or the input is inside the
+    /// deadzone. Outside of it the magnitude is rescaled so it starts at `0.0`
+    /// at the edge of the deadzone, see [`apply_radial_deadzone`]. Mouse
+    /// sources report deltas rather than positions, so those are returned
+    /// unchanged unless they are zero. The source mode is available through
+    /// [`get_analog_action_data`](#method.get_analog_action_data).
+    pub fn get_analog_action_vector(
+        &self,
+        controller: sys::InputHandle_t,
+        action: sys::InputAnalogActionHandle_t,
+        deadzone: f32,
+    ) -> Option<(f32, f32)> {
+        let data = self.get_analog_action_data(controller, action);
+        if !data.active {
+            return None;
+        }
+        match data.mode {
+            AnalogSourceMode::Mouse if data.x == 0.0 && data.y == 0.0 => None,
+            AnalogSourceMode::Mouse => Some((data.x, data.y)),
+            _ => apply_radial_deadzone(data.x, data.y, deadzone),
+        }
+    }
+}
+
+/// Applies a radial deadzone to a stick position in `-1.0..=1.0`.
+///
+/// Returns `None` if the position is within `deadzone` of the center.
+/// Otherwise the direction is kept and the magnitude is rescaled from
+/// `deadzone..=1.0` to `0.0..=1.0`, so there is no jump at the edge of the
+/// deadzone.
+pub fn apply_radial_deadzone(x: f32, y: f32, deadzone: f32) -> Option<(f32, f32)> {
+    let deadzone = deadzone.clamp(0.0, 1.0);
+    let magnitude = x.hypot(y);
+    if magnitude <= deadzone || deadzone >= 1.0 {
+        return None;
+    }
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+    let scale = scaled / magnitude;
+    Some((x * scale, y * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radial_deadzone() {
+        assert_eq!(apply_radial_deadzone(0.1, 0.1, 0.2), None);
+        assert_eq!(apply_radial_deadzone(0.0, 0.0, 0.0), None);
+        assert_eq!(apply_radial_deadzone(1.0, 0.0, 0.2), Some((1.0, 0.0)));
+
+        let (x, y) = apply_radial_deadzone(0.0, -0.6, 0.2).unwrap();
+        assert_eq!(x, 0.0);
+        assert!((y + 0.5).abs() < 1e-6);
+
+        // Diagonals past the unit circle are clamped to it
+        let (x, y) = apply_radial_deadzone(1.0, 1.0, 0.1).unwrap();
+        assert!((x.hypot(y) - 1.0).abs() < 1e-6);
+    }
+}
@@ -5,16 +5,22 @@ extern crate bitflags;
 #[macro_use]
 extern crate lazy_static;
 
-#[cfg(feature = "raw-bindings")]
+#[cfg(all(feature = "raw-bindings", not(feature = "mock")))]
 pub use steamworks_sys as sys;
-#[cfg(not(feature = "raw-bindings"))]
+#[cfg(all(not(feature = "raw-bindings"), not(feature = "mock")))]
 use steamworks_sys as sys;
+// The mock re-exports the bindings with the calls it backs replaced
+#[cfg(all(feature = "raw-bindings", feature = "mock"))]
+pub use crate::mock::sys;
+#[cfg(all(not(feature = "raw-bindings"), feature = "mock"))]
+use crate::mock::sys;
 
 use core::ffi::c_void;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -23,6 +29,8 @@ pub use crate::app::*;
 pub use crate::callback::*;
 pub use crate::error::*;
 pub use crate::friends::*;
+pub use crate::input::*;
+pub use crate::matchmaking::*;
 pub use crate::user::*;
 pub use crate::user_stats::*;
 pub use crate::utils::*;
@@ -31,6 +39,10 @@ mod app;
 mod callback;
 mod error;
 mod friends;
+mod input;
+mod matchmaking;
+#[cfg(feature = "mock")]
+pub mod mock;
 mod user;
 mod user_stats;
 mod utils;
@@ -64,11 +76,50 @@ impl<Manager> Clone for Client<Manager> {
 struct Inner<Manager> {
     _manager: Manager,
     callbacks: Mutex<Callbacks>,
+    /// Set once the current user's stats have been received
+    stats_loaded: Arc<AtomicBool>,
+    /// Lobbies joined through `Matchmaking`, left again on drop
+    lobbies: Arc<Mutex<Vec<SteamId>>>,
+    /// Whether the tracked lobbies are left on drop
+    leave_lobbies_on_drop: AtomicBool,
+    /// The callback set through `Utils::set_warning_callback`
+    warning_callback: Arc<utils::WarningCallback>,
+    /// Set while a `Utils::show_gamepad_text_input` hasn't been dismissed
+    gamepad_text_input_pending: Arc<AtomicBool>,
+    /// Run by `run_callbacks` once it has dispatched every pending callback
+    after_dispatch: AfterDispatch,
+    /// The listeners waiting for a response to an auth ticket, removed when
+    /// it is cancelled
+    auth_ticket_listeners: Arc<Mutex<HashMap<sys::HAuthTicket, ListenerToken>>>,
+}
+
+/// Work queued by listeners that needs the other callbacks of the same
+/// `run_callbacks` call, e.g. ones Steam sends in no particular order
+type AfterDispatch = Arc<Mutex<Vec<Box<dyn FnOnce() + Send + 'static>>>>;
+
+impl<Manager> Drop for Inner<Manager> {
+    fn drop(&mut self) {
+        // Runs before the manager shuts the api down
+        if !self.leave_lobbies_on_drop.load(Ordering::Acquire) {
+            return;
+        }
+        let lobbies = self.lobbies.lock().unwrap_or_else(|err| err.into_inner());
+        if lobbies.is_empty() {
+            return;
+        }
+        unsafe {
+            let mm = sys::SteamAPI_SteamMatchmaking_v009();
+            for lobby in lobbies.iter() {
+                sys::SteamAPI_ISteamMatchmaking_LeaveLobby(mm, lobby.0);
+            }
+        }
+    }
 }
 
 struct Callbacks {
     callbacks: HashMap<i32, Box<dyn FnMut(*mut c_void) + Send + 'static>>,
     call_results: HashMap<sys::SteamAPICall_t, Box<dyn FnOnce(*mut c_void, bool) + Send + 'static>>,
+    listeners: HashMap<i32, Vec<Listener>>,
 }
 
 unsafe impl<Manager: Send + Sync> Send for Inner<Manager> {}
@@ -76,6 +127,36 @@ unsafe impl<Manager: Send + Sync> Sync for Inner<Manager> {}
 unsafe impl<Manager: Send + Sync> Send for Client<Manager> {}
 unsafe impl<Manager: Send + Sync> Sync for Client<Manager> {}
 
+// The interface accessors hold the raw interface pointer Steam hands out,
+// so the compiler can't derive `Send`/`Sync` for them. Valve expects the
+// interfaces to be called from several threads: steam_api.h notes that
+// most calls allocate thread-local memory for their parameters, which
+// threads other than the one running callbacks release with
+// `SteamAPI_ReleaseCurrentThreadMemory`, see
+// https://partner.steamgames.com/doc/api/steam_api#SteamAPI_ReleaseCurrentThreadMemory.
+// Only callback dispatch is single threaded, and that goes through the
+// client's lock.
+//
+// These impls skip the compiler's check of every field, so a field added
+// to an accessor has to be shareable itself. The tests below only check
+// that the impls exist.
+unsafe impl<Manager: Send + Sync> Send for Apps<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for Apps<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for Friends<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for Friends<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for Input<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for Input<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for LeaderboardPager<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for LeaderboardPager<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for Matchmaking<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for Matchmaking<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for User<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for User<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for UserStats<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for UserStats<Manager> {}
+unsafe impl<Manager: Send + Sync> Send for Utils<Manager> {}
+unsafe impl<Manager: Send + Sync> Sync for Utils<Manager> {}
+
 /// Returns true if the app wasn't launched through steam and
 /// begins relaunching it, the app should exit as soon as possible.
 ///
@@ -119,9 +200,19 @@ impl Client<ClientManager> {
     /// * The game isn't running on the same user/level as the steam client
     /// * The user doesn't own a license for the game.
     /// * The app ID isn't completely set up.
+    /// * The steam_api library is from a different SDK than the one this
+    ///   crate targets (1.60) and is missing an interface version, or a
+    ///   callback struct doesn't have the id and size of the SDK bindings.
+    ///   Both are reported as [`SteamAPIInitError::CallbackMismatch`].
     pub fn init() -> SIResult<Client<ClientManager>> {
         static_assert_send::<Client<ClientManager>>();
         static_assert_sync::<Client<ClientManager>>();
+        if let Some(callback) = Self::mismatched_callback() {
+            return Err(SteamAPIInitError::CallbackMismatch(format!(
+                "{} doesn't match the SDK bindings",
+                callback
+            )));
+        }
         unsafe {
             let mut err_msg: sys::SteamErrMsg = [0; 1024];
             let result = Self::steam_api_init_flat(&mut err_msg);
@@ -130,29 +221,205 @@ impl Client<ClientManager> {
                 return Err(SteamAPIInitError::from_result_and_message(result, err_msg));
             }
 
+            if let Some(interface) = Self::missing_interface() {
+                sys::SteamAPI_Shutdown();
+                return Err(SteamAPIInitError::CallbackMismatch(format!(
+                    "the steam_api library doesn't provide {}",
+                    interface
+                )));
+            }
+
             sys::SteamAPI_ManualDispatch_Init();
             let client = Arc::new(Inner {
                 _manager: ClientManager { _priv: () },
                 callbacks: Mutex::new(Callbacks {
                     callbacks: HashMap::new(),
                     call_results: HashMap::new(),
+                    listeners: HashMap::new(),
                 }),
+                stats_loaded: Arc::new(AtomicBool::new(false)),
+                lobbies: Arc::new(Mutex::new(Vec::new())),
+                leave_lobbies_on_drop: AtomicBool::new(true),
+                warning_callback: Arc::new(RwLock::new(None)),
+                gamepad_text_input_pending: Arc::new(AtomicBool::new(false)),
+                after_dispatch: Arc::new(Mutex::new(Vec::new())),
+                auth_ticket_listeners: Arc::new(Mutex::new(HashMap::new())),
                 // networking_sockets_data: Mutex::new(NetworkingSocketsData {
                 //     sockets: Default::default(),
                 //     independent_connections: Default::default(),
                 //     connection_callback: Default::default(),
                 // }),
             });
+
+            let user = SteamId(sys::SteamAPI_ISteamUser_GetSteamID(
+                sys::SteamAPI_SteamUser_v023(),
+            ));
+            let stats_loaded = client.stats_loaded.clone();
+            register_listener(&client, move |v: UserStatsReceived| {
+                if v.steam_id == user && v.result.is_ok() {
+                    stats_loaded.store(true, Ordering::Release);
+                }
+                true
+            });
             Ok(Client { inner: client })
         }
     }
 
+    /// Returns the name of the first interface version this crate uses that
+    /// the loaded steam_api library doesn't provide.
+    ///
+    /// The versioned accessors return null when the library was built from a
+    /// different SDK, in which case the callback structs can't be trusted to
+    /// match either.
+    unsafe fn missing_interface() -> Option<&'static str> {
+        let interfaces: [(&'static str, bool); 7] = [
+            ("SteamApps008", sys::SteamAPI_SteamApps_v008().is_null()),
+            (
+                "SteamFriends017",
+                sys::SteamAPI_SteamFriends_v017().is_null(),
+            ),
+            ("SteamInput006", sys::SteamAPI_SteamInput_v006().is_null()),
+            (
+                "SteamMatchMaking009",
+                sys::SteamAPI_SteamMatchmaking_v009().is_null(),
+            ),
+            ("SteamUser023", sys::SteamAPI_SteamUser_v023().is_null()),
+            (
+                "STEAMUSERSTATS_INTERFACE_VERSION012",
+                sys::SteamAPI_SteamUserStats_v012().is_null(),
+            ),
+            ("SteamUtils010", sys::SteamAPI_SteamUtils_v010().is_null()),
+        ];
+        interfaces
+            .iter()
+            .find(|(_, missing)| *missing)
+            .map(|(name, _)| *name)
+    }
+
+    /// Returns the name of the first callback struct whose id or size differs
+    /// from the one this crate registers it with.
+    ///
+    /// Steam drops callbacks and call results that don't have the size it
+    /// expects, so a mismatch would otherwise only show as handlers that never
+    /// run.
+    fn mismatched_callback() -> Option<&'static str> {
+        fn mismatch<C: Callback, T>(id: i32) -> bool {
+            C::ID != id || C::SIZE as usize != std::mem::size_of::<T>()
+        }
+        let callbacks: [(&'static str, bool); 15] = [
+            (
+                "AvatarImageLoaded_t",
+                mismatch::<AvatarImageLoaded, sys::AvatarImageLoaded_t>(
+                    sys::AvatarImageLoaded_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "DlcInstalled_t",
+                mismatch::<DlcInstalled, sys::DlcInstalled_t>(
+                    sys::DlcInstalled_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "DurationControl_t",
+                mismatch::<DurationControl, sys::DurationControl_t>(
+                    sys::DurationControl_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "FloatingGamepadTextInputDismissed_t",
+                mismatch::<
+                    FloatingGamepadTextInputDismissed,
+                    sys::FloatingGamepadTextInputDismissed_t,
+                >(sys::FloatingGamepadTextInputDismissed_t_k_iCallback as i32),
+            ),
+            (
+                "GameOverlayActivated_t",
+                mismatch::<GameOverlayActivated, sys::GameOverlayActivated_t>(
+                    sys::GameOverlayActivated_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "GameRichPresenceJoinRequested_t",
+                mismatch::<GameRichPresenceJoinRequested, sys::GameRichPresenceJoinRequested_t>(
+                    sys::GameRichPresenceJoinRequested_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "GamepadTextInputDismissed_t",
+                mismatch::<GamepadTextInputDismissed, sys::GamepadTextInputDismissed_t>(
+                    sys::GamepadTextInputDismissed_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "GetAuthSessionTicketResponse_t",
+                mismatch::<AuthSessionTicketResponse, sys::GetAuthSessionTicketResponse_t>(
+                    sys::GetAuthSessionTicketResponse_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "GetTicketForWebApiResponse_t",
+                mismatch::<TicketForWebApiResponse, sys::GetTicketForWebApiResponse_t>(
+                    sys::GetTicketForWebApiResponse_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "PersonaStateChange_t",
+                mismatch::<PersonaStateChange, sys::PersonaStateChange_t>(
+                    sys::PersonaStateChange_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "UserAchievementIconFetched_t",
+                mismatch::<UserAchievementIconFetched, sys::UserAchievementIconFetched_t>(
+                    sys::UserAchievementIconFetched_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "UserAchievementStored_t",
+                mismatch::<UserAchievementStored, sys::UserAchievementStored_t>(
+                    sys::UserAchievementStored_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "UserStatsReceived_t",
+                mismatch::<UserStatsReceived, sys::UserStatsReceived_t>(
+                    sys::UserStatsReceived_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "UserStatsStored_t",
+                mismatch::<UserStatsStored, sys::UserStatsStored_t>(
+                    sys::UserStatsStored_t_k_iCallback as i32,
+                ),
+            ),
+            (
+                "ValidateAuthTicketResponse_t",
+                mismatch::<ValidateAuthTicketResponse, sys::ValidateAuthTicketResponse_t>(
+                    sys::ValidateAuthTicketResponse_t_k_iCallback as i32,
+                ),
+            ),
+        ];
+        callbacks
+            .iter()
+            .find(|(_, mismatch)| *mismatch)
+            .map(|(name, _)| *name)
+    }
+
     /// Attempts to initialize the steamworks api with the APP_ID
     /// without full API integration through SteamAPI_InitFlat
     /// and returns a client to access the rest of the api.
     ///
     /// This should only ever have one instance per a program.
     ///
+    /// # App ID precedence
+    ///
+    /// Steam reads the app ID from the `SteamAppId` environment variable
+    /// before falling back to a `steam_appid.txt` in the current working
+    /// directory. This sets `SteamAppId` and `SteamGameId` for the whole
+    /// process before initializing, so `app_id` wins over both an existing
+    /// `steam_appid.txt` and any value the process was launched with. Child
+    /// processes spawned afterwards inherit the variables.
+    ///
     /// # Errors
     ///
     /// This can fail if:
@@ -160,6 +427,8 @@ impl Client<ClientManager> {
     /// * The game isn't running on the same user/level as the steam client
     /// * The user doesn't own a license for the game.
     /// * The app ID isn't completely set up.
+    ///
+    /// See [`Client::init`] for the full list.
     pub fn init_app<ID: Into<AppId>>(app_id: ID) -> SIResult<Client<ClientManager>> {
         let app_id = app_id.into().0.to_string();
         std::env::set_var("SteamAppId", &app_id);
@@ -201,20 +470,57 @@ where
                     ) {
                         // The &{val} pattern here is to avoid taking a reference to a packed field
                         // Since the value here is Copy, we can just copy it and borrow the copy
-                        if let Some(cb) = callbacks.call_results.remove(&{ apicall.m_hAsyncCall }) {
+                        let cb = callbacks.call_results.remove(&{ apicall.m_hAsyncCall });
+                        // Unlocked so the handler can start another call, e.g. for the next page
+                        drop(callbacks);
+                        if let Some(cb) = cb {
                             cb(apicall_result.as_mut_ptr() as *mut _, failed);
                         }
                     }
                 } else {
+                    if let Some(listeners) = callbacks.listeners.get_mut(&callback.m_iCallback) {
+                        let count = listeners.len();
+                        listeners.retain_mut(|l| l.call(callback.m_pubParam as *mut _));
+                        if listeners.len() != count {
+                            // Listeners registered together with a finished one go as well
+                            callbacks.remove_inactive_listeners();
+                        }
+                    }
                     if let Some(cb) = callbacks.callbacks.get_mut(&callback.m_iCallback) {
                         cb(callback.m_pubParam as *mut _);
                     }
                 }
                 sys::SteamAPI_ManualDispatch_FreeLastCallback(pipe);
             }
+            let tasks = std::mem::take(&mut *self.inner.after_dispatch.lock().unwrap());
+            if !tasks.is_empty() {
+                for task in tasks {
+                    task();
+                }
+                let mut callbacks = self.inner.callbacks.lock().unwrap();
+                callbacks.remove_inactive_listeners();
+            }
         }
     }
 
+    /// Returns the steam pipe this client communicates over.
+    ///
+    /// This is an escape hatch for sharing the initialized steam api with
+    /// code using `steamworks-sys` directly, instead of initializing it a
+    /// second time. Callbacks on this pipe are dispatched by
+    /// [`run_callbacks`](#method.run_callbacks), so don't dispatch them
+    /// manually as well.
+    pub fn steam_pipe(&self) -> sys::HSteamPipe {
+        unsafe { Manager::get_pipe() }
+    }
+
+    /// Returns the handle of the steam user this client is logged in as.
+    ///
+    /// See [`steam_pipe`](#method.steam_pipe).
+    pub fn steam_user_handle(&self) -> sys::HSteamUser {
+        unsafe { sys::SteamAPI_GetHSteamUser() }
+    }
+
     /// Registers the passed function as a callback for the
     /// given type.
     ///
@@ -257,13 +563,40 @@ where
         unsafe {
             let friends = sys::SteamAPI_SteamFriends_v017();
             debug_assert!(!friends.is_null());
+            let user = sys::SteamAPI_SteamUser_v023();
+            debug_assert!(!user.is_null());
             Friends {
                 friends: friends,
+                user,
                 inner: self.inner.clone(),
             }
         }
     }
 
+    /// Returns an accessor to the steam input interface
+    pub fn input(&self) -> Input<Manager> {
+        unsafe {
+            let input = sys::SteamAPI_SteamInput_v006();
+            debug_assert!(!input.is_null());
+            Input {
+                input,
+                _inner: self.inner.clone(),
+            }
+        }
+    }
+
+    /// Returns an accessor to the steam matchmaking interface
+    pub fn matchmaking(&self) -> Matchmaking<Manager> {
+        unsafe {
+            let mm = sys::SteamAPI_SteamMatchmaking_v009();
+            debug_assert!(!mm.is_null());
+            Matchmaking {
+                mm,
+                _inner: self.inner.clone(),
+            }
+        }
+    }
+
     /// Returns an accessor to the steam user interface
     pub fn user(&self) -> User<Manager> {
         unsafe {
@@ -409,10 +742,13 @@ impl GameId {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(not(feature = "mock"))]
     use serial_test::serial;
 
     use super::*;
 
+    // Needs a running steam client, the mock only backs user and user stats
+    #[cfg(not(feature = "mock"))]
     #[test]
     #[serial]
     fn basic_test() {
@@ -426,7 +762,7 @@ mod tests {
         let apps = client.apps();
         println!("Apps");
         println!("IsInstalled(480): {}", apps.is_app_installed(AppId(480)));
-        println!("InstallDir(480): {}", apps.app_install_dir(AppId(480)));
+        println!("InstallDir(480): {:?}", apps.app_install_dir(AppId(480)));
         println!("BuildId: {}", apps.app_build_id());
         println!("AppOwner: {:?}", apps.app_owner());
         println!("Langs: {:?}", apps.available_game_languages());
@@ -457,4 +793,29 @@ mod tests {
         let steamid = SteamId(76561198174976054);
         assert_eq!("STEAM_0:0:107355163", steamid.steamid32());
     }
+
+    #[test]
+    fn accessors_are_send_and_sync() {
+        static_assert_send::<Apps<ClientManager>>();
+        static_assert_sync::<Apps<ClientManager>>();
+        static_assert_send::<Friends<ClientManager>>();
+        static_assert_sync::<Friends<ClientManager>>();
+        static_assert_send::<Input<ClientManager>>();
+        static_assert_sync::<Input<ClientManager>>();
+        static_assert_send::<LeaderboardPager<ClientManager>>();
+        static_assert_sync::<LeaderboardPager<ClientManager>>();
+        static_assert_send::<Matchmaking<ClientManager>>();
+        static_assert_sync::<Matchmaking<ClientManager>>();
+        static_assert_send::<User<ClientManager>>();
+        static_assert_sync::<User<ClientManager>>();
+        static_assert_send::<UserStats<ClientManager>>();
+        static_assert_sync::<UserStats<ClientManager>>();
+        static_assert_send::<Utils<ClientManager>>();
+        static_assert_sync::<Utils<ClientManager>>();
+    }
+
+    #[test]
+    fn callbacks_match_sdk() {
+        assert_eq!(Client::mismatched_callback(), None);
+    }
 }
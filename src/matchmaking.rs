@@ -0,0 +1,252 @@
+use super::*;
+
+use std::net::Ipv4Addr;
+
+const CALLBACK_BASE_ID: i32 = 500;
+
+bitflags! {
+    /// Which of the user's server lists a favorite game entry belongs to
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[repr(C)]
+    pub struct FavoriteFlags: u32 {
+        const NONE     = 0x00;
+        const FAVORITE = 0x01;
+        const HISTORY  = 0x02;
+    }
+}
+
+/// Who can find and join a lobby
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LobbyType {
+    /// Only joinable through invites
+    Private,
+    /// Joinable by friends and through invites
+    FriendsOnly,
+    /// Joinable by anyone and shown in lobby searches
+    Public,
+    /// Joinable by anyone but not shown in lobby searches
+    Invisible,
+}
+
+/// A game server in the user's favorites or history list
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FavoriteGame {
+    pub app_id: AppId,
+    pub ip: Ipv4Addr,
+    pub conn_port: u16,
+    pub query_port: u16,
+    pub flags: FavoriteFlags,
+    /// The last time the user played on the server, in Unix epoch format
+    pub last_played: u32,
+}
+
+/// Access to the steam matchmaking interface
+pub struct Matchmaking<Manager> {
+    pub(crate) mm: *mut sys::ISteamMatchmaking,
+    pub(crate) _inner: Arc<Inner<Manager>>,
+}
+
+impl<Manager> Matchmaking<Manager> {
+    /// Returns the number of entries in the user's favorites and history lists.
+    pub fn favorite_game_count(&self) -> i32 {
+        unsafe { sys::SteamAPI_ISteamMatchmaking_GetFavoriteGameCount(self.mm) }
+    }
+
+    /// Returns the favorite game entry at `index`.
+    ///
+    /// `index` should be less than [`favorite_game_count`](#method.favorite_game_count).
+    pub fn get_favorite_game(&self, index: i32) -> Option<FavoriteGame> {
+        unsafe {
+            let mut app_id = 0;
+            let mut ip = 0;
+            let mut conn_port = 0;
+            let mut query_port = 0;
+            let mut flags = 0;
+            let mut last_played = 0;
+            if sys::SteamAPI_ISteamMatchmaking_GetFavoriteGame(
+                self.mm,
+                index,
+                &mut app_id,
+                &mut ip,
+                &mut conn_port,
+                &mut query_port,
+                &mut flags,
+                &mut last_played,
+            ) {
+                Some(FavoriteGame {
+                    app_id: AppId(app_id),
+                    ip: Ipv4Addr::from(ip),
+                    conn_port,
+                    query_port,
+                    flags: FavoriteFlags::from_bits_truncate(flags),
+                    last_played,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Adds a game server to the user's favorites or history list.
+    ///
+    /// The list is kept by Steam, so entries persist between sessions.
+    /// Returns the index of the new entry.
+    pub fn add_favorite_game(
+        &self,
+        app_id: AppId,
+        ip: Ipv4Addr,
+        conn_port: u16,
+        query_port: u16,
+        flags: FavoriteFlags,
+        last_played: u32,
+    ) -> i32 {
+        unsafe {
+            sys::SteamAPI_ISteamMatchmaking_AddFavoriteGame(
+                self.mm,
+                app_id.0,
+                ip.into(),
+                conn_port,
+                query_port,
+                flags.bits(),
+                last_played,
+            )
+        }
+    }
+
+    /// Removes a game server from the user's favorites or history list.
+    ///
+    /// Returns whether a matching entry was found and removed.
+    pub fn remove_favorite_game(
+        &self,
+        app_id: AppId,
+        ip: Ipv4Addr,
+        conn_port: u16,
+        query_port: u16,
+        flags: FavoriteFlags,
+    ) -> bool {
+        unsafe {
+            sys::SteamAPI_ISteamMatchmaking_RemoveFavoriteGame(
+                self.mm,
+                app_id.0,
+                ip.into(),
+                conn_port,
+                query_port,
+                flags.bits(),
+            )
+        }
+    }
+
+    /// Creates a lobby and joins it.
+    ///
+    /// The lobby is left again when the client is dropped, see
+    /// [`set_leave_lobbies_on_drop`](#method.set_leave_lobbies_on_drop).
+    pub fn create_lobby<F>(&self, ty: LobbyType, max_members: u32, cb: F)
+    where
+        F: FnOnce(Result<SteamId, SteamError>) + 'static + Send,
+    {
+        let ty = match ty {
+            LobbyType::Private => sys::ELobbyType::k_ELobbyTypePrivate,
+            LobbyType::FriendsOnly => sys::ELobbyType::k_ELobbyTypeFriendsOnly,
+            LobbyType::Public => sys::ELobbyType::k_ELobbyTypePublic,
+            LobbyType::Invisible => sys::ELobbyType::k_ELobbyTypeInvisible,
+        };
+        let lobbies = self._inner.lobbies.clone();
+        unsafe {
+            let api_call =
+                sys::SteamAPI_ISteamMatchmaking_CreateLobby(self.mm, ty, max_members as i32);
+            register_call_result::<sys::LobbyCreated_t, _, _>(
+                &self._inner,
+                api_call,
+                CALLBACK_BASE_ID + 13,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        let lobby = SteamId(v.m_ulSteamIDLobby);
+                        track_lobby(&lobbies, lobby);
+                        Ok(lobby)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Joins an existing lobby.
+    ///
+    /// If the lobby can't be entered `cb` receives the reason Steam gave.
+    /// The lobby is left again when the client is dropped, see
+    /// [`set_leave_lobbies_on_drop`](#method.set_leave_lobbies_on_drop).
+    pub fn join_lobby<F>(&self, lobby: SteamId, cb: F)
+    where
+        F: FnOnce(Result<SteamId, LobbyEnterError>) + 'static + Send,
+    {
+        let lobbies = self._inner.lobbies.clone();
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamMatchmaking_JoinLobby(self.mm, lobby.0);
+            register_call_result::<sys::LobbyEnter_t, _, _>(
+                &self._inner,
+                api_call,
+                CALLBACK_BASE_ID + 4,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(LobbyEnterError::IOFailure)
+                    } else if v.m_EChatRoomEnterResponse
+                        != sys::EChatRoomEnterResponse::k_EChatRoomEnterResponseSuccess as u32
+                    {
+                        Err(LobbyEnterError::from_response(v.m_EChatRoomEnterResponse))
+                    } else {
+                        let lobby = SteamId(v.m_ulSteamIDLobby);
+                        track_lobby(&lobbies, lobby);
+                        Ok(lobby)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Leaves a lobby the current user is a member of.
+    pub fn leave_lobby(&self, lobby: SteamId) {
+        self._inner.lobbies.lock().unwrap().retain(|l| *l != lobby);
+        unsafe {
+            sys::SteamAPI_ISteamMatchmaking_LeaveLobby(self.mm, lobby.0);
+        }
+    }
+
+    /// Sets whether lobbies created or joined through this interface are left
+    /// when the client is dropped. Enabled by default.
+    ///
+    /// Leaving on drop keeps players from showing up in a lobby after their
+    /// game has exited. Games that want to rejoin the same lobby after
+    /// restarting can disable it.
+    pub fn set_leave_lobbies_on_drop(&self, leave: bool) {
+        self._inner
+            .leave_lobbies_on_drop
+            .store(leave, Ordering::Release);
+    }
+
+    /// Returns the maximum number of members allowed in `lobby`.
+    ///
+    /// Returns `0` if no limit is set or the lobby's metadata isn't available.
+    pub fn get_lobby_member_limit(&self, lobby: SteamId) -> i32 {
+        unsafe { sys::SteamAPI_ISteamMatchmaking_GetLobbyMemberLimit(self.mm, lobby.0) }
+    }
+
+    /// Sets the maximum number of members allowed in `lobby`.
+    ///
+    /// Only the lobby owner can change the limit. Returns whether the
+    /// request was accepted.
+    pub fn set_lobby_member_limit(&self, lobby: SteamId, max: i32) -> bool {
+        unsafe { sys::SteamAPI_ISteamMatchmaking_SetLobbyMemberLimit(self.mm, lobby.0, max) }
+    }
+}
+
+fn track_lobby(lobbies: &Mutex<Vec<SteamId>>, lobby: SteamId) {
+    let mut lobbies = lobbies.lock().unwrap();
+    if !lobbies.contains(&lobby) {
+        lobbies.push(lobby);
+    }
+}
@@ -0,0 +1,586 @@
+//! An in-memory stand-in for the steam client, enabled by the `mock` feature.
+//!
+//! With the feature enabled `Client::init` succeeds without a running steam
+//! client and the [`User`](../struct.User.html) and [`UserStats`](../struct.UserStats.html)
+//! interfaces are backed by the state configured here, so game code using them
+//! can be unit tested in CI. Requesting stats queues a `UserStatsReceived`
+//! callback and storing them queues a `UserStatsStored` callback, both delivered
+//! by `run_callbacks` as usual.
+//!
+//! Only the parts of `User` and `UserStats` used for the current user's stats and
+//! achievements are backed. The other interfaces still talk to the real steam
+//! api, which isn't initialized in this mode, so they must not be used.
+//!
+//! The state is shared by the whole process. Tests using it should run serially
+//! and call [`reset`] first.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use steamworks::*;
+//! mock::reset();
+//! mock::define_stat_i32("KILLS", 0);
+//! mock::define_achievement("FIRST_BLOOD");
+//!
+//! let client = Client::init().unwrap();
+//! let user_stats = client.user_stats();
+//! user_stats.set_stat_i32("KILLS", 1).unwrap();
+//! user_stats.achievement("FIRST_BLOOD").set().unwrap();
+//! assert_eq!(user_stats.get_stat_i32("KILLS"), Ok(1));
+//! ```
+
+use super::*;
+
+use std::collections::VecDeque;
+use std::sync::MutexGuard;
+
+struct MockState {
+    steam_id: u64,
+    level: i32,
+    stats_i32: HashMap<CString, i32>,
+    stats_f32: HashMap<CString, f32>,
+    /// Kept in definition order so they can be looked up by index
+    achievements: Vec<(CString, bool)>,
+    /// How many of the next stores should report a failure
+    store_failures: u32,
+    /// Whether stores queue `UserStatsStored` before the achievement callbacks
+    stats_stored_first: bool,
+    /// Achievements unlocked since the last successful store
+    unstored: Vec<CString>,
+    /// Pending callbacks as `(id, payload)`
+    queue: VecDeque<(i32, Vec<u64>)>,
+    /// The callback last handed out, kept alive until it is freed
+    current: Option<Vec<u64>>,
+}
+
+impl MockState {
+    fn new() -> MockState {
+        MockState {
+            steam_id: 76561197960265729,
+            level: 0,
+            stats_i32: HashMap::new(),
+            stats_f32: HashMap::new(),
+            achievements: Vec::new(),
+            store_failures: 0,
+            stats_stored_first: false,
+            unstored: Vec::new(),
+            queue: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    fn queue_callback<T: Copy>(&mut self, id: i32, value: T) {
+        let mut payload = vec![0u64; std::mem::size_of::<T>().div_ceil(8)];
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                &value as *const T as *const u8,
+                payload.as_mut_ptr() as *mut u8,
+                std::mem::size_of::<T>(),
+            );
+        }
+        self.queue.push_back((id, payload));
+    }
+
+    fn achievement(&mut self, name: &CStr) -> Option<&mut bool> {
+        self.achievements
+            .iter_mut()
+            .find(|(n, _)| n.as_c_str() == name)
+            .map(|(_, achieved)| achieved)
+    }
+}
+
+lazy_static! {
+    static ref STATE: Mutex<MockState> = Mutex::new(MockState::new());
+}
+
+fn state() -> MutexGuard<'static, MockState> {
+    // A panicking test shouldn't take every later test down with it
+    STATE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Clears all stats, achievements and pending callbacks and restores the
+/// default steam id and level.
+pub fn reset() {
+    *state() = MockState::new();
+}
+
+/// Sets the steam id reported for the current user.
+pub fn set_steam_id(id: SteamId) {
+    state().steam_id = id.0;
+}
+
+/// Sets the steam level reported for the current user.
+pub fn set_level(level: u32) {
+    state().level = level as i32;
+}
+
+/// Makes the next `count` stores report a failed `UserStatsStored` callback.
+pub fn fail_next_stores(count: u32) {
+    state().store_failures = count;
+}
+
+/// Makes stores queue their `UserStatsStored` callback before the
+/// `UserAchievementStored` ones instead of after, steam doesn't document the order.
+pub fn set_stats_stored_first(first: bool) {
+    state().stats_stored_first = first;
+}
+
+/// Defines an integer stat with its current value.
+///
+/// Like on steam, only defined stats can be read or written.
+pub fn define_stat_i32(name: &str, value: i32) {
+    state().stats_i32.insert(CString::new(name).unwrap(), value);
+}
+
+/// Defines a float stat with its current value.
+///
+/// Like on steam, only defined stats can be read or written.
+pub fn define_stat_f32(name: &str, value: f32) {
+    state().stats_f32.insert(CString::new(name).unwrap(), value);
+}
+
+/// Defines a locked achievement.
+pub fn define_achievement(name: &str) {
+    state()
+        .achievements
+        .push((CString::new(name).unwrap(), false));
+}
+
+/// Replacements for the parts of the raw bindings the mock backs.
+///
+/// Everything not defined here is the real binding, re-exported as is.
+#[allow(non_snake_case, clippy::missing_safety_doc)]
+pub mod sys {
+    pub use steamworks_sys::*;
+
+    use super::state;
+    use std::ffi::CStr;
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::ptr::NonNull;
+
+    pub unsafe fn SteamAPI_InitFlat(_pOutErrMsg: *mut SteamErrMsg) -> ESteamAPIInitResult {
+        ESteamAPIInitResult::k_ESteamAPIInitResult_OK
+    }
+
+    pub unsafe fn SteamAPI_Shutdown() {}
+
+    pub unsafe fn SteamAPI_ManualDispatch_Init() {}
+
+    pub unsafe fn SteamAPI_GetHSteamPipe() -> HSteamPipe {
+        1
+    }
+
+    pub unsafe fn SteamAPI_ManualDispatch_RunFrame(_hSteamPipe: HSteamPipe) {}
+
+    pub unsafe fn SteamAPI_ManualDispatch_GetNextCallback(
+        _hSteamPipe: HSteamPipe,
+        pCallbackMsg: *mut CallbackMsg_t,
+    ) -> bool {
+        let mut state = state();
+        let (id, mut payload) = match state.queue.pop_front() {
+            Some(next) => next,
+            None => return false,
+        };
+        *pCallbackMsg = CallbackMsg_t {
+            m_hSteamUser: 1,
+            m_iCallback: id,
+            m_pubParam: payload.as_mut_ptr() as *mut _,
+            m_cubParam: (payload.len() * 8) as c_int,
+        };
+        state.current = Some(payload);
+        true
+    }
+
+    pub unsafe fn SteamAPI_ManualDispatch_FreeLastCallback(_hSteamPipe: HSteamPipe) {
+        state().current = None;
+    }
+
+    pub unsafe fn SteamAPI_ManualDispatch_GetAPICallResult(
+        _hSteamPipe: HSteamPipe,
+        _hSteamAPICall: SteamAPICall_t,
+        _pCallback: *mut c_void,
+        _cubCallback: c_int,
+        _iCallbackExpected: c_int,
+        _pbFailed: *mut bool,
+    ) -> bool {
+        false
+    }
+
+    // The remaining interfaces aren't backed by the mock, they only report
+    // an interface so `Client::init` succeeds.
+    pub unsafe fn SteamAPI_SteamApps_v008() -> *mut ISteamApps {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamFriends_v017() -> *mut ISteamFriends {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamInput_v006() -> *mut ISteamInput {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamMatchmaking_v009() -> *mut ISteamMatchmaking {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamUtils_v010() -> *mut ISteamUtils {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamUser_v023() -> *mut ISteamUser {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_SteamUserStats_v012() -> *mut ISteamUserStats {
+        NonNull::dangling().as_ptr()
+    }
+
+    pub unsafe fn SteamAPI_ISteamUser_GetSteamID(_self_: *mut ISteamUser) -> uint64_steamid {
+        state().steam_id
+    }
+
+    pub unsafe fn SteamAPI_ISteamUser_GetPlayerSteamLevel(_self_: *mut ISteamUser) -> c_int {
+        state().level
+    }
+
+    pub unsafe fn SteamAPI_ISteamUser_BLoggedOn(_self_: *mut ISteamUser) -> bool {
+        true
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_RequestCurrentStats(
+        _self_: *mut ISteamUserStats,
+    ) -> bool {
+        let mut state = state();
+        let steam_id = state.steam_id;
+        state.queue_callback(
+            UserStatsReceived_t_k_iCallback as i32,
+            UserStatsReceived_t {
+                m_nGameID: 0,
+                m_eResult: EResult::k_EResultOK,
+                m_steamIDUser: CSteamID {
+                    m_steamid: CSteamID_SteamID_t {
+                        m_unAll64Bits: steam_id,
+                    },
+                },
+            },
+        );
+        true
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_StoreStats(_self_: *mut ISteamUserStats) -> bool {
+        let mut state = state();
+        let result = if state.store_failures > 0 {
+            state.store_failures -= 1;
+            EResult::k_EResultFail
+        } else {
+            EResult::k_EResultOK
+        };
+        let stats_stored = UserStatsStored_t {
+            m_nGameID: 0,
+            m_eResult: result,
+        };
+        if state.stats_stored_first {
+            state.queue_callback(UserStatsStored_t_k_iCallback as i32, stats_stored);
+        }
+        if result == EResult::k_EResultOK {
+            for name in std::mem::take(&mut state.unstored) {
+                let mut stored = UserAchievementStored_t {
+                    m_nGameID: 0,
+                    m_bGroupAchievement: false,
+                    m_rgchAchievementName: [0; 128],
+                    m_nCurProgress: 0,
+                    m_nMaxProgress: 0,
+                };
+                for (dst, src) in stored.m_rgchAchievementName.iter_mut().zip(name.as_bytes()) {
+                    *dst = *src as c_char;
+                }
+                state.queue_callback(UserAchievementStored_t_k_iCallback as i32, stored);
+            }
+        }
+        if !state.stats_stored_first {
+            state.queue_callback(UserStatsStored_t_k_iCallback as i32, stats_stored);
+        }
+        true
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_ResetAllStats(
+        _self_: *mut ISteamUserStats,
+        bAchievementsToo: bool,
+    ) -> bool {
+        let mut state = state();
+        state.stats_i32.values_mut().for_each(|v| *v = 0);
+        state.stats_f32.values_mut().for_each(|v| *v = 0.0);
+        if bAchievementsToo {
+            state.achievements.iter_mut().for_each(|(_, a)| *a = false);
+        }
+        true
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_GetStatInt32(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+        pData: *mut int32,
+    ) -> bool {
+        match state().stats_i32.get(CStr::from_ptr(pchName)) {
+            Some(value) => {
+                *pData = *value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_SetStatInt32(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+        nData: int32,
+    ) -> bool {
+        match state().stats_i32.get_mut(CStr::from_ptr(pchName)) {
+            Some(value) => {
+                *value = nData;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_GetStatFloat(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+        pData: *mut f32,
+    ) -> bool {
+        match state().stats_f32.get(CStr::from_ptr(pchName)) {
+            Some(value) => {
+                *pData = *value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_SetStatFloat(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+        fData: f32,
+    ) -> bool {
+        match state().stats_f32.get_mut(CStr::from_ptr(pchName)) {
+            Some(value) => {
+                *value = fData;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_GetAchievement(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+        pbAchieved: *mut bool,
+    ) -> bool {
+        match state().achievement(CStr::from_ptr(pchName)) {
+            Some(achieved) => {
+                *pbAchieved = *achieved;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_SetAchievement(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+    ) -> bool {
+        let mut state = state();
+        let name = CStr::from_ptr(pchName);
+        match state.achievement(name) {
+            Some(achieved) => {
+                if !*achieved {
+                    *achieved = true;
+                    state.unstored.push(name.to_owned());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_ClearAchievement(
+        _self_: *mut ISteamUserStats,
+        pchName: *const c_char,
+    ) -> bool {
+        match state().achievement(CStr::from_ptr(pchName)) {
+            Some(achieved) => {
+                *achieved = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_GetNumAchievements(
+        _self_: *mut ISteamUserStats,
+    ) -> uint32 {
+        state().achievements.len() as uint32
+    }
+
+    pub unsafe fn SteamAPI_ISteamUserStats_GetAchievementName(
+        _self_: *mut ISteamUserStats,
+        iAchievement: uint32,
+    ) -> *const c_char {
+        // The names live in the state until the next `reset`, like steam's
+        // strings live until the schema changes
+        match state().achievements.get(iAchievement as usize) {
+            Some((name, _)) => name.as_ptr(),
+            None => c"".as_ptr(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn mock_user_stats() {
+        reset();
+        define_stat_i32("KILLS", 3);
+        define_stat_f32("DISTANCE", 1.5);
+        define_achievement("FIRST_BLOOD");
+
+        let client = Client::init().unwrap();
+        let user_stats = client.user_stats();
+
+        assert_eq!(user_stats.get_stat_i32("KILLS"), Ok(3));
+        user_stats.set_stat_i32("KILLS", 4).unwrap();
+        assert_eq!(user_stats.get_stat_i32("KILLS"), Ok(4));
+        assert_eq!(user_stats.get_stat_f32("DISTANCE"), Ok(1.5));
+        assert_eq!(
+            user_stats.get_stat_i32("DISTANCE"),
+            Err(UserStatsError::StatsNotLoaded)
+        );
+
+        user_stats.request_current_stats();
+        client.run_callbacks();
+        assert_eq!(
+            user_stats.get_stat_i32("DISTANCE"),
+            Err(UserStatsError::WrongType)
+        );
+        assert_eq!(
+            user_stats.set_stat_i32("UNKNOWN", 1),
+            Err(UserStatsError::StatNotFound)
+        );
+        assert_eq!(
+            user_stats.get_stat_f32("BAD\0NAME"),
+            Err(UserStatsError::InvalidName)
+        );
+
+        let achievement = user_stats.achievement("FIRST_BLOOD");
+        assert_eq!(achievement.get(), Ok(false));
+        achievement.set().unwrap();
+        assert_eq!(achievement.get(), Ok(true));
+        assert_eq!(
+            user_stats.get_achievement_names(),
+            Ok(vec!["FIRST_BLOOD".to_owned()])
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn mock_callbacks() {
+        reset();
+        set_steam_id(SteamId(76561198040894045));
+        define_achievement("FIRST_BLOOD");
+
+        let client = Client::init().unwrap();
+        assert_eq!(client.user().steam_id(), SteamId(76561198040894045));
+
+        let user_stats = client.user_stats();
+        assert_eq!(
+            user_stats.achievement("UNKNOWN").is_achieved(),
+            Err(UserStatsError::StatsNotLoaded)
+        );
+
+        let received = Arc::new(Mutex::new(None));
+        let received2 = received.clone();
+        let _cb = client.register_callback(move |v: UserStatsReceived| {
+            *received2.lock().unwrap() = Some(v.steam_id);
+        });
+        user_stats.request_current_stats();
+        client.run_callbacks();
+
+        assert_eq!(*received.lock().unwrap(), Some(SteamId(76561198040894045)));
+        assert_eq!(
+            user_stats.achievement("UNKNOWN").is_achieved(),
+            Err(UserStatsError::AchievementNotFound)
+        );
+        assert_eq!(
+            user_stats.achievement("FIRST_BLOOD").is_achieved(),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn mock_store_retry() {
+        reset();
+        let client = Client::init().unwrap();
+        let user_stats = client.user_stats();
+
+        let result = Arc::new(Mutex::new(None));
+        fail_next_stores(2);
+        let result2 = result.clone();
+        user_stats.store_stats_with_retry(3, move |r| *result2.lock().unwrap() = Some(r));
+        for _ in 0..3 {
+            client.run_callbacks();
+        }
+        assert_eq!(*result.lock().unwrap(), Some(Ok(())));
+
+        fail_next_stores(2);
+        let result2 = result.clone();
+        user_stats.store_stats_with_retry(2, move |r| *result2.lock().unwrap() = Some(r));
+        for _ in 0..3 {
+            client.run_callbacks();
+        }
+        assert_eq!(*result.lock().unwrap(), Some(Err(SteamError::Generic)));
+    }
+
+    #[test]
+    #[serial]
+    fn mock_store_async() {
+        reset();
+        define_achievement("FIRST_BLOOD");
+        define_achievement("SECOND_BLOOD");
+        let client = Client::init().unwrap();
+        let user_stats = client.user_stats();
+        user_stats.achievement("FIRST_BLOOD").set().unwrap();
+
+        let result = Arc::new(Mutex::new(None));
+        let result2 = result.clone();
+        user_stats.store_stats_async(move |r| *result2.lock().unwrap() = Some(r));
+        client.run_callbacks();
+        assert_eq!(
+            *result.lock().unwrap(),
+            Some(Ok(vec!["FIRST_BLOOD".to_owned()]))
+        );
+
+        set_stats_stored_first(true);
+        user_stats.achievement("SECOND_BLOOD").set().unwrap();
+        let result2 = result.clone();
+        user_stats.store_stats_async(move |r| *result2.lock().unwrap() = Some(r));
+        client.run_callbacks();
+        assert_eq!(
+            *result.lock().unwrap(),
+            Some(Ok(vec!["SECOND_BLOOD".to_owned()]))
+        );
+
+        fail_next_stores(1);
+        let result2 = result.clone();
+        user_stats.store_stats_async(move |r| *result2.lock().unwrap() = Some(r));
+        client.run_callbacks();
+        assert_eq!(*result.lock().unwrap(), Some(Err(SteamError::Generic)));
+    }
+}
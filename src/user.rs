@@ -1,5 +1,10 @@
 use super::*;
 
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+
+const CALLBACK_BASE_ID: i32 = 100;
+
 /// Access to the steam user interface
 pub struct User<Manager> {
     pub(crate) user: *mut sys::ISteamUser,
@@ -21,4 +26,759 @@ impl<Manager> User<Manager> {
     pub fn logged_on(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamUser_BLoggedOn(self.user) }
     }
+
+    /// Requests the current user's playtime limits for the current app.
+    ///
+    /// Steam also sends a [`DurationControl`] callback on its own whenever the
+    /// user crosses a playtime threshold.
+    pub fn get_duration_control<F>(&self, cb: F)
+    where
+        F: FnOnce(Result<DurationControl, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUser_GetDurationControl(self.user);
+            register_call_result::<sys::DurationControl_t, _, _>(
+                &self._inner,
+                api_call,
+                CALLBACK_BASE_ID + 67,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(DurationControl::from_raw_struct(v))
+                    })
+                },
+            );
+        }
+    }
+
+    /// Creates an auth session ticket that a game server or web backend can
+    /// use to authenticate the current user.
+    ///
+    /// The ticket can be sent right away, but `cb` is called once Steam has
+    /// validated it, which the other end needs before it can verify it. Cancel
+    /// the ticket with
+    /// [`cancel_auth_ticket`](#method.cancel_auth_ticket)
+    /// when the session ends.
+    pub fn authentication_session_ticket<F>(&self, cb: F) -> Ticket
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        self.session_ticket(None, cb)
+    }
+
+    /// Like [`authentication_session_ticket`](#method.authentication_session_ticket)
+    /// but the ticket is only valid when redeemed by `identity`, e.g. the
+    /// steam id of the game server it is sent to.
+    pub fn authentication_session_ticket_for<F>(&self, identity: SteamId, cb: F) -> Ticket
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        self.session_ticket(Some(identity), cb)
+    }
+
+    /// Requests an auth ticket to authenticate the current user against a web
+    /// backend.
+    ///
+    /// `identity` names the service the ticket is for, which the backend
+    /// passes to the `AuthenticateUserTicket` web api. `cb` receives the
+    /// ticket once Steam created it. The returned handle can be passed to
+    /// [`cancel_auth_ticket`](#method.cancel_auth_ticket). `cb` receives
+    /// [`SteamError::InvalidName`] right away if `identity` contains a nul
+    /// byte.
+    pub fn get_auth_ticket_for_web_api<F>(&self, identity: &str, cb: F) -> AuthTicket
+    where
+        F: FnOnce(Result<Ticket, SteamError>) + 'static + Send,
+    {
+        let identity = match CString::new(identity) {
+            Ok(identity) => identity,
+            Err(_) => {
+                cb(Err(SteamError::InvalidName));
+                return AuthTicket(sys::k_HAuthTicketInvalid);
+            }
+        };
+        unsafe {
+            // Held until the listener is registered so the response can't be missed
+            let mut callbacks = self._inner.callbacks.lock().unwrap();
+            let handle =
+                sys::SteamAPI_ISteamUser_GetAuthTicketForWebApi(self.user, identity.as_ptr());
+            if handle == sys::k_HAuthTicketInvalid {
+                drop(callbacks);
+                cb(Err(SteamError::Generic));
+            } else {
+                let token = ListenerToken::new();
+                let listeners = self._inner.auth_ticket_listeners.clone();
+                listeners.lock().unwrap().insert(handle, token.clone());
+                let mut cb = Some(cb);
+                add_listener(&mut callbacks, &token, move |v: TicketForWebApiResponse| {
+                    if v.ticket.0 != handle {
+                        return true;
+                    }
+                    listeners.lock().unwrap().remove(&handle);
+                    if let Some(cb) = cb.take() {
+                        cb(v.result.map(|data| Ticket {
+                            handle: v.ticket,
+                            data,
+                        }))
+                    }
+                    false
+                });
+            }
+            AuthTicket(handle)
+        }
+    }
+
+    /// Requests an encrypted app ticket which a backend can use to verify the
+    /// current user owns the game, without a connection to Steam.
+    ///
+    /// `data` is included in the ticket. `cb` receives the ticket, which is
+    /// also available from
+    /// [`get_encrypted_app_ticket`](#method.get_encrypted_app_ticket) until
+    /// the next request. Only one request can be in flight at a time.
+    pub fn request_encrypted_app_ticket<F>(&self, data: &[u8], cb: F)
+    where
+        F: FnOnce(Result<Vec<u8>, SteamError>) + 'static + Send,
+    {
+        let mut data = data.to_vec();
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUser_RequestEncryptedAppTicket(
+                self.user,
+                data.as_mut_ptr() as *mut _,
+                data.len() as i32,
+            );
+            register_call_result::<sys::EncryptedAppTicketResponse_t, _, _>(
+                &self._inner,
+                api_call,
+                CALLBACK_BASE_ID + 54,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        encrypted_app_ticket(sys::SteamAPI_SteamUser_v023())
+                            .ok_or(SteamError::Generic)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Returns the encrypted app ticket received by the last
+    /// [`request_encrypted_app_ticket`](#method.request_encrypted_app_ticket).
+    pub fn get_encrypted_app_ticket(&self) -> Option<Vec<u8>> {
+        unsafe { encrypted_app_ticket(self.user) }
+    }
+
+    /// Cancels an auth session ticket, ending the sessions created with it.
+    ///
+    /// Tickets aren't cancelled when a [`Ticket`] is dropped, so call this
+    /// once the ticket is no longer needed, e.g. when disconnecting from the
+    /// server it was sent to. A callback still waiting for the ticket is
+    /// dropped without being called.
+    pub fn cancel_auth_ticket(&self, ticket: AuthTicket) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_CancelAuthTicket(self.user, ticket.0);
+        }
+        let token = self
+            ._inner
+            .auth_ticket_listeners
+            .lock()
+            .unwrap()
+            .remove(&ticket.0);
+        if let Some(token) = token {
+            remove_listeners(&self._inner, &token);
+        }
+    }
+
+    /// Starts an auth session for `user` with the ticket they sent.
+    ///
+    /// This only checks the ticket locally, Steam sends a
+    /// [`ValidateAuthTicketResponse`] callback once it has validated it and
+    /// again whenever the user's session changes. End the session with
+    /// [`end_auth_session`](#method.end_auth_session) when the user leaves.
+    pub fn begin_auth_session(&self, ticket: &[u8], user: SteamId) -> Result<(), AuthSessionError> {
+        unsafe {
+            let res = sys::SteamAPI_ISteamUser_BeginAuthSession(
+                self.user,
+                ticket.as_ptr() as *const _,
+                ticket.len() as i32,
+                user.0,
+            );
+            if res == sys::EBeginAuthSessionResult::k_EBeginAuthSessionResultOK {
+                Ok(())
+            } else {
+                Err(res.into())
+            }
+        }
+    }
+
+    /// Ends the auth session started for `user` by
+    /// [`begin_auth_session`](#method.begin_auth_session).
+    pub fn end_auth_session(&self, user: SteamId) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_EndAuthSession(self.user, user.0);
+        }
+    }
+
+    fn session_ticket<F>(&self, identity: Option<SteamId>, cb: F) -> Ticket
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let identity = identity.map(|id| {
+                let mut identity: sys::SteamNetworkingIdentity = std::mem::zeroed();
+                identity.m_eType =
+                    sys::ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_SteamID;
+                identity.m_cbSize = std::mem::size_of::<u64>() as i32;
+                identity.__bindgen_anon_1.m_steamID64 = id.0;
+                identity
+            });
+            let mut data = vec![0u8; 1024];
+            let mut len = 0;
+            // Held until the listener is registered so the response can't be missed
+            let mut callbacks = self._inner.callbacks.lock().unwrap();
+            let handle = sys::SteamAPI_ISteamUser_GetAuthSessionTicket(
+                self.user,
+                data.as_mut_ptr() as *mut _,
+                data.len() as i32,
+                &mut len,
+                identity
+                    .as_ref()
+                    .map_or(std::ptr::null(), |identity| identity as *const _),
+            );
+            data.truncate(len as usize);
+            if handle == sys::k_HAuthTicketInvalid {
+                drop(callbacks);
+                cb(Err(SteamError::Generic));
+            } else {
+                let token = ListenerToken::new();
+                let listeners = self._inner.auth_ticket_listeners.clone();
+                listeners.lock().unwrap().insert(handle, token.clone());
+                let mut cb = Some(cb);
+                add_listener(
+                    &mut callbacks,
+                    &token,
+                    move |v: AuthSessionTicketResponse| {
+                        if v.ticket.0 != handle {
+                            return true;
+                        }
+                        listeners.lock().unwrap().remove(&handle);
+                        if let Some(cb) = cb.take() {
+                            cb(v.result)
+                        }
+                        false
+                    },
+                );
+            }
+            Ticket {
+                handle: AuthTicket(handle),
+                data,
+            }
+        }
+    }
+
+    /// Starts recording voice and returns a stream of the recorded frames.
+    ///
+    /// Recording stops when the stream is dropped.
+    pub fn voice_stream(&self) -> VoiceStream<Manager> {
+        unsafe {
+            sys::SteamAPI_ISteamUser_StartVoiceRecording(self.user);
+        }
+        VoiceStream {
+            user: self.user,
+            _inner: self._inner.clone(),
+        }
+    }
+
+    /// Starts recording voice.
+    ///
+    /// Recorded frames are read with [`get_voice`](#method.get_voice).
+    /// [`voice_stream`](#method.voice_stream) wraps this and stops recording
+    /// once it is dropped.
+    pub fn start_voice_recording(&self) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_StartVoiceRecording(self.user);
+        }
+    }
+
+    /// Stops recording voice.
+    ///
+    /// Steam keeps recording for a short while after this to avoid cutting
+    /// off the end of a sentence, so keep calling
+    /// [`get_voice`](#method.get_voice) until it returns
+    /// [`VoiceError::NotRecording`].
+    pub fn stop_voice_recording(&self) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_StopVoiceRecording(self.user);
+        }
+    }
+
+    /// Returns the size in bytes of the compressed voice data recorded so far.
+    ///
+    /// Returns `Ok(None)` when no new voice data has been recorded.
+    pub fn get_available_voice(&self) -> Result<Option<u32>, VoiceError> {
+        unsafe { available_voice(self.user) }
+    }
+
+    /// Returns the compressed voice data recorded since the last call.
+    ///
+    /// Returns `Ok(None)` when no new voice data has been recorded. This
+    /// should be polled frequently (e.g. every frame) to keep the latency low.
+    pub fn get_voice(&self) -> Result<Option<Vec<u8>>, VoiceError> {
+        unsafe { voice(self.user) }
+    }
+
+    /// Returns the sample rate the voice decoder works at.
+    ///
+    /// Decompressing at this rate avoids resampling.
+    pub fn get_voice_optimal_sample_rate(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamUser_GetVoiceOptimalSampleRate(self.user) }
+    }
+
+    /// Decompresses voice data recorded by another user into signed 16 bit
+    /// mono PCM samples at `sample_rate`, in native byte order.
+    ///
+    /// `sample_rate` must be between 11025 and 48000.
+    pub fn decompress_voice(
+        &self,
+        compressed: &[u8],
+        sample_rate: u32,
+    ) -> Result<Vec<u8>, VoiceError> {
+        unsafe {
+            // Enough for about half a second of audio, grown if Steam needs more
+            let mut output = vec![0u8; 20 * 1024];
+            loop {
+                let mut written = 0;
+                let result = sys::SteamAPI_ISteamUser_DecompressVoice(
+                    self.user,
+                    compressed.as_ptr() as *const _,
+                    compressed.len() as u32,
+                    output.as_mut_ptr() as *mut _,
+                    output.len() as u32,
+                    &mut written,
+                    sample_rate,
+                );
+                match result {
+                    sys::EVoiceResult::k_EVoiceResultOK => {
+                        output.truncate(written as usize);
+                        return Ok(output);
+                    }
+                    sys::EVoiceResult::k_EVoiceResultBufferTooSmall
+                        if written as usize > output.len() =>
+                    {
+                        output.resize(written as usize, 0);
+                    }
+                    err => return Err(err.into()),
+                }
+            }
+        }
+    }
+
+    /// Returns the level of the current user's badge for the game.
+    ///
+    /// `series` is the badge series, starting at 1, and `foil` selects the
+    /// foil badge of it. Returns `0` if the user doesn't have the badge.
+    pub fn get_game_badge_level(&self, series: i32, foil: bool) -> i32 {
+        unsafe { sys::SteamAPI_ISteamUser_GetGameBadgeLevel(self.user, series, foil) }
+    }
+
+    /// Tells Steam which game server the current user is playing on, so
+    /// friends can join them.
+    pub fn advertise_game(&self, server: SteamId, ip: Ipv4Addr, port: u16) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_AdvertiseGame(self.user, server.0, ip.into(), port);
+        }
+    }
+
+    /// Returns whether the current user appears to be behind a NAT device.
+    ///
+    /// Only updated when the user logs on, so it can be stale.
+    pub fn is_behind_nat(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUser_BIsBehindNAT(self.user) }
+    }
+
+    /// Returns the folder Steam stores the current user's data for the game
+    /// in.
+    ///
+    /// Prefer the steam cloud for saves, this folder isn't synced.
+    pub fn get_user_data_folder(&self) -> Option<PathBuf> {
+        unsafe {
+            let mut buffer = vec![0; 2048];
+            if !sys::SteamAPI_ISteamUser_GetUserDataFolder(
+                self.user,
+                buffer.as_mut_ptr(),
+                buffer.len() as i32,
+            ) {
+                return None;
+            }
+            let path = CStr::from_ptr(buffer.as_ptr());
+            if path.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(path.to_string_lossy().into_owned()))
+            }
+        }
+    }
+
+    /// Returns whether the current user is logged in with an anonymous account.
+    ///
+    /// This covers both anonymous user accounts and anonymous game server logins.
+    ///
+    /// Steam doesn't expose an account's creation time to clients, so this and
+    /// [`is_anonymous_user_account`](#method.is_anonymous_user_account) are the
+    /// trust signals available locally. Both are derived from the current user's
+    /// steam id and work without a network request.
+    pub fn is_anonymous_account(&self) -> bool {
+        let account_type = self.account_type();
+        account_type == sys::EAccountType::k_EAccountTypeAnonUser as u32
+            || account_type == sys::EAccountType::k_EAccountTypeAnonGameServer as u32
+    }
+
+    /// Returns whether the current user is logged in with an anonymous user account.
+    ///
+    /// Unlike [`is_anonymous_account`](#method.is_anonymous_account) this excludes
+    /// anonymous game server logins.
+    pub fn is_anonymous_user_account(&self) -> bool {
+        self.account_type() == sys::EAccountType::k_EAccountTypeAnonUser as u32
+    }
+
+    fn account_type(&self) -> u32 {
+        unsafe {
+            let bits = sys::CSteamID_SteamID_t {
+                m_unAll64Bits: self.steam_id().0,
+            };
+            bits.m_comp.m_EAccountType()
+        }
+    }
+}
+
+/// A handle to an auth session ticket
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AuthTicket(pub(crate) sys::HAuthTicket);
+
+/// An auth ticket created by [`User::authentication_session_ticket`] or
+/// [`User::get_auth_ticket_for_web_api`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ticket {
+    /// The handle to cancel the ticket with
+    pub handle: AuthTicket,
+    /// The ticket to send to the game server or backend
+    pub data: Vec<u8>,
+}
+
+/// Callback sent once Steam has validated an auth session ticket
+#[derive(Clone, Debug)]
+pub struct AuthSessionTicketResponse {
+    pub ticket: AuthTicket,
+    pub result: Result<(), SteamError>,
+}
+
+unsafe impl Callback for AuthSessionTicketResponse {
+    const ID: i32 = CALLBACK_BASE_ID + 63;
+    const SIZE: i32 = std::mem::size_of::<sys::GetAuthSessionTicketResponse_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GetAuthSessionTicketResponse_t);
+        AuthSessionTicketResponse {
+            ticket: AuthTicket(val.m_hAuthTicket),
+            result: if val.m_eResult == sys::EResult::k_EResultOK {
+                Ok(())
+            } else {
+                Err(val.m_eResult.into())
+            },
+        }
+    }
+}
+
+/// Callback sent once Steam has created a ticket requested by
+/// [`User::get_auth_ticket_for_web_api`]
+#[derive(Clone, Debug)]
+pub struct TicketForWebApiResponse {
+    pub ticket: AuthTicket,
+    /// The ticket to send to the backend
+    pub result: Result<Vec<u8>, SteamError>,
+}
+
+unsafe impl Callback for TicketForWebApiResponse {
+    const ID: i32 = CALLBACK_BASE_ID + 68;
+    const SIZE: i32 = std::mem::size_of::<sys::GetTicketForWebApiResponse_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GetTicketForWebApiResponse_t);
+        TicketForWebApiResponse {
+            ticket: AuthTicket(val.m_hAuthTicket),
+            result: if val.m_eResult == sys::EResult::k_EResultOK {
+                let len = (val.m_cubTicket.max(0) as usize).min(val.m_rgubTicket.len());
+                Ok(val.m_rgubTicket[..len].to_vec())
+            } else {
+                Err(val.m_eResult.into())
+            },
+        }
+    }
+}
+
+/// Callback sent with the result of validating a user's auth session ticket
+///
+/// Sent for sessions started with [`User::begin_auth_session`], and again if
+/// the session becomes invalid later on, e.g. when the user gets VAC banned.
+#[derive(Clone, Debug)]
+pub struct ValidateAuthTicketResponse {
+    /// The user whose ticket was validated
+    pub steam_id: SteamId,
+    /// The owner of the game, which differs from `steam_id` when it is
+    /// borrowed through family sharing
+    pub owner_steam_id: SteamId,
+    pub response: Result<(), AuthSessionValidateError>,
+}
+
+unsafe impl Callback for ValidateAuthTicketResponse {
+    const ID: i32 = CALLBACK_BASE_ID + 43;
+    const SIZE: i32 = std::mem::size_of::<sys::ValidateAuthTicketResponse_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::ValidateAuthTicketResponse_t);
+        ValidateAuthTicketResponse {
+            steam_id: SteamId(val.m_SteamID.m_steamid.m_unAll64Bits),
+            owner_steam_id: SteamId(val.m_OwnerSteamID.m_steamid.m_unAll64Bits),
+            response: if val.m_eAuthSessionResponse
+                == sys::EAuthSessionResponse::k_EAuthSessionResponseOK
+            {
+                Ok(())
+            } else {
+                Err(val.m_eAuthSessionResponse.into())
+            },
+        }
+    }
+}
+
+/// A stream of compressed voice frames recorded from the current user
+///
+/// Created by [`User::voice_stream`]. Frames can be sent to other players as
+/// is and played back with `DecompressVoice` on their end.
+pub struct VoiceStream<Manager> {
+    user: *mut sys::ISteamUser,
+    _inner: Arc<Inner<Manager>>,
+}
+
+impl<Manager> VoiceStream<Manager> {
+    /// Returns the next compressed voice frame.
+    ///
+    /// Returns `Ok(None)` when no new voice data has been recorded since
+    /// the last call. This should be polled frequently (e.g. every frame)
+    /// to keep the latency low.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, VoiceError> {
+        unsafe { voice(self.user) }
+    }
+}
+
+/// Reads the last encrypted app ticket received
+unsafe fn encrypted_app_ticket(user: *mut sys::ISteamUser) -> Option<Vec<u8>> {
+    let mut ticket = vec![0u8; 1024];
+    loop {
+        let mut len = 0;
+        if sys::SteamAPI_ISteamUser_GetEncryptedAppTicket(
+            user,
+            ticket.as_mut_ptr() as *mut _,
+            ticket.len() as i32,
+            &mut len,
+        ) {
+            ticket.truncate(len as usize);
+            return Some(ticket);
+        }
+        if len as usize <= ticket.len() {
+            return None;
+        }
+        ticket.resize(len as usize, 0);
+    }
+}
+
+/// Returns the size of the compressed voice data available, `Ok(None)` if
+/// there is none
+unsafe fn available_voice(user: *mut sys::ISteamUser) -> Result<Option<u32>, VoiceError> {
+    let mut available = 0;
+    let result =
+        sys::SteamAPI_ISteamUser_GetAvailableVoice(user, &mut available, std::ptr::null_mut(), 0);
+    match result {
+        sys::EVoiceResult::k_EVoiceResultOK => Ok(Some(available)),
+        sys::EVoiceResult::k_EVoiceResultNoData => Ok(None),
+        err => Err(err.into()),
+    }
+}
+
+/// Reads the compressed voice data recorded since the last call
+unsafe fn voice(user: *mut sys::ISteamUser) -> Result<Option<Vec<u8>>, VoiceError> {
+    let available = match available_voice(user)? {
+        Some(available) => available,
+        None => return Ok(None),
+    };
+
+    let mut frame = vec![0u8; available as usize];
+    let mut written = 0;
+    let result = sys::SteamAPI_ISteamUser_GetVoice(
+        user,
+        true,
+        frame.as_mut_ptr() as *mut _,
+        frame.len() as u32,
+        &mut written,
+        false,
+        std::ptr::null_mut(),
+        0,
+        std::ptr::null_mut(),
+        0,
+    );
+    match result {
+        sys::EVoiceResult::k_EVoiceResultOK => {
+            frame.truncate(written as usize);
+            Ok(Some(frame))
+        }
+        sys::EVoiceResult::k_EVoiceResultNoData => Ok(None),
+        err => Err(err.into()),
+    }
+}
+
+impl<Manager> Drop for VoiceStream<Manager> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SteamAPI_ISteamUser_StopVoiceRecording(self.user);
+        }
+    }
+}
+
+/// How much progress a player playing under duration control still earns
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DurationControlProgress {
+    /// Full progress
+    Full,
+    /// No more than half progress
+    Half,
+    /// No progress
+    None,
+    /// The player has played 3 hours today and must exit soon
+    ExitSoon3h,
+    /// The player has played 5 hours in the last 24 hours and must exit soon
+    ExitSoon5h,
+    /// The player isn't allowed to play at night and must exit soon
+    ExitSoonNight,
+}
+
+/// Which playtime notification Steam is showing the player
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DurationControlNotification {
+    None,
+    /// The player has been playing for an hour
+    OneHour,
+    /// The player has been playing for three hours
+    ThreeHours,
+    /// The player has switched to half progress
+    HalfProgress,
+    /// The player has switched to no progress
+    NoProgress,
+    /// See [`DurationControlProgress::ExitSoon3h`]
+    ExitSoon3h,
+    /// See [`DurationControlProgress::ExitSoon5h`]
+    ExitSoon5h,
+    /// See [`DurationControlProgress::ExitSoonNight`]
+    ExitSoonNight,
+}
+
+/// Callback sent when the current user's playtime limits change
+///
+/// Steam sends this by itself in regions that regulate playtime, whenever the
+/// user crosses a threshold. It is also the result of
+/// [`User::get_duration_control`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DurationControl {
+    pub app_id: AppId,
+    /// Whether duration control applies to the current user
+    pub applicable: bool,
+    /// Seconds played in the last 5 hours
+    pub seconds_last_5h: i32,
+    pub progress: DurationControlProgress,
+    pub notification: DurationControlNotification,
+    /// Seconds played today
+    pub seconds_today: i32,
+    /// Seconds left before the player must stop, negative if unlimited
+    pub seconds_remaining: i32,
+}
+
+impl DurationControl {
+    /// Returns whether the player has to stop playing soon.
+    pub fn must_exit(&self) -> bool {
+        matches!(
+            self.progress,
+            DurationControlProgress::ExitSoon3h
+                | DurationControlProgress::ExitSoon5h
+                | DurationControlProgress::ExitSoonNight
+        )
+    }
+
+    fn from_raw_struct(val: &sys::DurationControl_t) -> Self {
+        DurationControl {
+            app_id: AppId(val.m_appid),
+            applicable: val.m_bApplicable,
+            seconds_last_5h: val.m_csecsLast5h,
+            progress: match val.m_progress {
+                sys::EDurationControlProgress::k_EDurationControlProgress_Half => {
+                    DurationControlProgress::Half
+                }
+                sys::EDurationControlProgress::k_EDurationControlProgress_None => {
+                    DurationControlProgress::None
+                }
+                sys::EDurationControlProgress::k_EDurationControl_ExitSoon_3h => {
+                    DurationControlProgress::ExitSoon3h
+                }
+                sys::EDurationControlProgress::k_EDurationControl_ExitSoon_5h => {
+                    DurationControlProgress::ExitSoon5h
+                }
+                sys::EDurationControlProgress::k_EDurationControl_ExitSoon_Night => {
+                    DurationControlProgress::ExitSoonNight
+                }
+                _ => DurationControlProgress::Full,
+            },
+            notification: match val.m_notification {
+                sys::EDurationControlNotification::k_EDurationControlNotification_1Hour => {
+                    DurationControlNotification::OneHour
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_3Hours => {
+                    DurationControlNotification::ThreeHours
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_HalfProgress => {
+                    DurationControlNotification::HalfProgress
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_NoProgress => {
+                    DurationControlNotification::NoProgress
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_ExitSoon_3h => {
+                    DurationControlNotification::ExitSoon3h
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_ExitSoon_5h => {
+                    DurationControlNotification::ExitSoon5h
+                }
+                sys::EDurationControlNotification::k_EDurationControlNotification_ExitSoon_Night => {
+                    DurationControlNotification::ExitSoonNight
+                }
+                _ => DurationControlNotification::None,
+            },
+            seconds_today: val.m_csecsToday,
+            seconds_remaining: val.m_csecsRemaining,
+        }
+    }
+}
+
+unsafe impl Callback for DurationControl {
+    const ID: i32 = CALLBACK_BASE_ID + 67;
+    const SIZE: i32 = std::mem::size_of::<sys::DurationControl_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::DurationControl_t);
+        DurationControl::from_raw_struct(val)
+    }
 }
@@ -1,6 +1,8 @@
+mod leaderboard;
 mod stat_callback;
 pub mod stats;
 
+pub use self::leaderboard::*;
 pub use self::stat_callback::*;
 use super::*;
 
@@ -12,6 +14,18 @@ pub struct UserStats<Manager> {
 
 const CALLBACK_BASE_ID: i32 = 1100;
 
+/// An achievement with its global unlock percentage
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MostAchievedAchievement {
+    /// The API name of the achievement
+    pub name: String,
+    /// The percentage of users who have unlocked the achievement
+    pub percent: f32,
+    /// Whether the current user has unlocked the achievement
+    pub achieved: bool,
+}
+
 impl<Manager> UserStats<Manager> {
     /// Triggers a [`UserStatsReceived`](./struct.UserStatsReceived.html) callback.
     pub fn request_current_stats(&self) {
@@ -20,6 +34,116 @@ impl<Manager> UserStats<Manager> {
         }
     }
 
+    /// Asynchronously downloads the stats and achievements of another user.
+    ///
+    /// Also triggers a [`UserStatsReceived`](./struct.UserStatsReceived.html) callback.
+    /// `cb` receives the same value, so check its `result` to tell whether the download
+    /// succeeded. Once it has, the user's stats can be read with
+    /// [`get_user_stat_i32()`](#method.get_user_stat_i32),
+    /// [`get_user_stat_f32()`](#method.get_user_stat_f32) and
+    /// [`get_user_achievement()`](#method.get_user_achievement).
+    pub fn request_user_stats<F>(&self, user: SteamId, cb: F)
+    where
+        F: FnOnce(Result<UserStatsReceived, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUserStats_RequestUserStats(self.user_stats, user.0);
+            register_call_result::<sys::UserStatsReceived_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 1,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else {
+                        Ok(UserStatsReceived {
+                            steam_id: SteamId(v.m_steamIDUser.m_steamid.m_unAll64Bits),
+                            game_id: GameId(v.m_nGameID),
+                            result: match v.m_eResult {
+                                sys::EResult::k_EResultOK => Ok(()),
+                                err => Err(err.into()),
+                            },
+                        })
+                    })
+                },
+            );
+        }
+    }
+
+    /// Returns the value of an integer stat of another user.
+    ///
+    /// Returns `None` if the user's stats haven't been requested with
+    /// [`request_user_stats()`](#method.request_user_stats) yet, or the stat doesn't
+    /// exist or isn't an integer.
+    pub fn get_user_stat_i32(&self, user: SteamId, name: &str) -> Option<i32> {
+        let name = CString::new(name).ok()?;
+
+        let mut value = 0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserStatInt32(
+                self.user_stats,
+                user.0,
+                name.as_ptr() as *const _,
+                &mut value,
+            )
+        };
+        if success {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of a float stat of another user.
+    ///
+    /// Returns `None` if the user's stats haven't been requested with
+    /// [`request_user_stats()`](#method.request_user_stats) yet, or the stat doesn't
+    /// exist or isn't a float.
+    pub fn get_user_stat_f32(&self, user: SteamId, name: &str) -> Option<f32> {
+        let name = CString::new(name).ok()?;
+
+        let mut value = 0.0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserStatFloat(
+                self.user_stats,
+                user.0,
+                name.as_ptr() as *const _,
+                &mut value,
+            )
+        };
+        if success {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether another user has unlocked an achievement.
+    ///
+    /// Returns `None` if the user's stats haven't been requested with
+    /// [`request_user_stats()`](#method.request_user_stats) yet or the achievement
+    /// doesn't exist. Combine with
+    /// [`get_achievement_icon()`](./stats/struct.AchievementHelper.html#method.get_achievement_icon)
+    /// for comparison screens, noting that icons follow the current user's progress.
+    pub fn get_user_achievement(&self, user: SteamId, name: &str) -> Option<bool> {
+        let name = CString::new(name).ok()?;
+
+        let mut achieved = false;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserAchievement(
+                self.user_stats,
+                user.0,
+                name.as_ptr() as *const _,
+                &mut achieved,
+            )
+        };
+        if success {
+            Some(achieved)
+        } else {
+            None
+        }
+    }
+
     /// Asynchronously fetch the data for the percentage of players who have received each achievement
     /// for the current game globally.
     /// 
@@ -54,6 +178,235 @@ impl<Manager> UserStats<Manager> {
         }
     }
 
+    /// Asynchronously fetches the number of players currently playing the game, both
+    /// online and offline.
+    ///
+    /// `cb` receives `Err(SteamError::IOFailure)` if Steam couldn't get the count.
+    pub fn get_number_of_current_players<F>(&self, cb: F)
+    where
+        F: FnOnce(Result<i32, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUserStats_GetNumberOfCurrentPlayers(self.user_stats);
+            register_call_result::<sys::NumberOfCurrentPlayers_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 7,
+                move |v, io_error| {
+                    cb(if io_error || v.m_bSuccess == 0 {
+                        Err(SteamError::IOFailure)
+                    } else {
+                        Ok(v.m_cPlayers)
+                    })
+                },
+            );
+        }
+    }
+
+    /// Asynchronously fetches the global stats of the game, aggregated over all users.
+    ///
+    /// `history_days` is the number of days of history to download, up to 60. Once `cb`
+    /// has been called successfully the values can be read with
+    /// [`get_global_stat_i64()`](#method.get_global_stat_i64) and
+    /// [`get_global_stat_f64()`](#method.get_global_stat_f64).
+    pub fn request_global_stats<F>(&self, history_days: i32, cb: F)
+    where
+        F: FnOnce(Result<GameId, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let api_call =
+                sys::SteamAPI_ISteamUserStats_RequestGlobalStats(self.user_stats, history_days);
+            register_call_result::<sys::GlobalStatsReceived_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 12,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_eResult != sys::EResult::k_EResultOK {
+                        Err(v.m_eResult.into())
+                    } else {
+                        Ok(GameId(v.m_nGameID))
+                    })
+                },
+            );
+        }
+    }
+
+    /// Returns the global value of an integer stat.
+    ///
+    /// Fails with [`UserStatsError::WrongType`] if the stat is an aggregated float, and
+    /// with [`UserStatsError::StatNotFound`] if the stat doesn't exist, isn't aggregated or
+    /// [`request_global_stats()`](#method.request_global_stats) hasn't completed successfully
+    /// yet.
+    pub fn get_global_stat_i64(&self, name: &str) -> Result<i64, UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
+
+        let mut value = 0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatInt64(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                &mut value,
+            )
+        };
+        if success {
+            return Ok(value);
+        }
+        let mut other = 0.0;
+        let other_type = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatDouble(
+                self.user_stats,
+                name.as_ptr(),
+                &mut other,
+            )
+        };
+        if other_type {
+            Err(UserStatsError::WrongType)
+        } else {
+            Err(UserStatsError::StatNotFound)
+        }
+    }
+
+    /// Returns the global value of a float stat.
+    ///
+    /// Fails with [`UserStatsError::WrongType`] if the stat is an aggregated integer, and
+    /// with [`UserStatsError::StatNotFound`] if the stat doesn't exist, isn't aggregated or
+    /// [`request_global_stats()`](#method.request_global_stats) hasn't completed successfully
+    /// yet.
+    pub fn get_global_stat_f64(&self, name: &str) -> Result<f64, UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
+
+        let mut value = 0.0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatDouble(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                &mut value,
+            )
+        };
+        if success {
+            return Ok(value);
+        }
+        let mut other = 0;
+        let other_type = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatInt64(
+                self.user_stats,
+                name.as_ptr(),
+                &mut other,
+            )
+        };
+        if other_type {
+            Err(UserStatsError::WrongType)
+        } else {
+            Err(UserStatsError::StatNotFound)
+        }
+    }
+
+    /// Returns the daily history of the global value of an integer stat, most recent
+    /// day first.
+    ///
+    /// At most `days` entries are returned, fewer if less history has been downloaded by
+    /// [`request_global_stats()`](#method.request_global_stats). The list is empty if the
+    /// stats haven't been received or the stat doesn't exist.
+    pub fn get_global_stat_history_i64(&self, name: &str, days: usize) -> Vec<i64> {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut history = vec![0; days];
+        let filled = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatHistoryInt64(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                history.as_mut_ptr(),
+                std::mem::size_of_val(history.as_slice()) as u32,
+            )
+        };
+        history.truncate(filled.max(0) as usize);
+        history
+    }
+
+    /// Returns the daily history of the global value of a float stat, most recent day
+    /// first.
+    ///
+    /// See [`get_global_stat_history_i64()`](#method.get_global_stat_history_i64).
+    pub fn get_global_stat_history_f64(&self, name: &str, days: usize) -> Vec<f64> {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut history = vec![0.0; days];
+        let filled = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetGlobalStatHistoryDouble(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                history.as_mut_ptr(),
+                std::mem::size_of_val(history.as_slice()) as u32,
+            )
+        };
+        history.truncate(filled.max(0) as usize);
+        history
+    }
+
+    /// Returns the global unlock percentage of every achievement, keyed by API name.
+    ///
+    /// Requires [`request_global_achievement_percentages()`](#method.request_global_achievement_percentages)
+    /// to have completed successfully. Achievements without data are left out.
+    pub fn global_achievement_percentages(&self) -> HashMap<String, f32> {
+        let num = self.get_num_achievements();
+        let mut percentages = HashMap::new();
+
+        for i in 0..num {
+            if let Some(name) = self.achievement_name(i) {
+                if let Ok(percent) = self.achievement(&name).get_achievement_achieved_percent() {
+                    percentages.insert(name, percent);
+                }
+            }
+        }
+        percentages
+    }
+
+    /// Returns the achievements ordered from the most to the least unlocked, with
+    /// their global unlock percentage.
+    ///
+    /// Requires [`request_global_achievement_percentages()`](#method.request_global_achievement_percentages)
+    /// to have completed successfully, otherwise the list is empty. The last entry is
+    /// the rarest achievement.
+    pub fn get_most_achieved_achievement_info(&self) -> Vec<MostAchievedAchievement> {
+        let mut achievements = Vec::new();
+        let mut name = [0 as std::os::raw::c_char; sys::k_cchStatNameMax as usize];
+        let mut percent = 0.0;
+        let mut achieved = false;
+        unsafe {
+            let mut iter = sys::SteamAPI_ISteamUserStats_GetMostAchievedAchievementInfo(
+                self.user_stats,
+                name.as_mut_ptr(),
+                name.len() as u32,
+                &mut percent,
+                &mut achieved,
+            );
+            while iter != -1 {
+                achievements.push(MostAchievedAchievement {
+                    name: CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned(),
+                    percent,
+                    achieved,
+                });
+                iter = sys::SteamAPI_ISteamUserStats_GetNextMostAchievedAchievementInfo(
+                    self.user_stats,
+                    iter,
+                    name.as_mut_ptr(),
+                    name.len() as u32,
+                    &mut percent,
+                    &mut achieved,
+                );
+            }
+        }
+        achievements
+    }
+
     /// Send the changed stats and achievements data to the server for permanent storage.
     ///
     /// * Triggers a [`UserStatsStored`](../struct.UserStatsStored.html) callback if successful.
@@ -62,12 +415,111 @@ impl<Manager> UserStats<Manager> {
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
     /// and a successful [`UserStatsReceived`](./struct.UserStatsReceived.html) callback processed.
-    pub fn store_stats(&self) -> Result<(), ()> {
+    pub fn store_stats(&self) -> Result<(), UserStatsError> {
         let success = unsafe { sys::SteamAPI_ISteamUserStats_StoreStats(self.user_stats) };
         if success {
             Ok(())
+        } else if !self.stats_loaded() {
+            Err(UserStatsError::StatsNotLoaded)
         } else {
-            Err(())
+            Err(UserStatsError::StoreFailed)
+        }
+    }
+
+    /// Send the changed stats and achievements data to the server, retrying if
+    /// storing fails.
+    ///
+    /// Calls [`store_stats()`](#method.store_stats) and, as long as the
+    /// resulting [`UserStatsStored`](../struct.UserStatsStored.html) callback
+    /// reports a failure, stores again up to `max_attempts` attempts in total.
+    /// `cb` receives the result of the last attempt once
+    /// [`Client::run_callbacks`](../struct.Client.html#method.run_callbacks)
+    /// has processed it, or an error right away if the stats couldn't be stored
+    /// at all (e.g. they haven't been received yet).
+    ///
+    /// Other stores started while this one is pending are reported to the same
+    /// `UserStatsStored` callback and can't be told apart from it.
+    pub fn store_stats_with_retry<F>(&self, max_attempts: u32, cb: F)
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        unsafe {
+            if !sys::SteamAPI_ISteamUserStats_StoreStats(self.user_stats) {
+                cb(Err(SteamError::Generic));
+                return;
+            }
+            let mut attempts = 1;
+            let mut cb = Some(cb);
+            register_listener(&self.inner, move |v: UserStatsStored| {
+                if v.result.is_err()
+                    && attempts < max_attempts
+                    && sys::SteamAPI_ISteamUserStats_StoreStats(sys::SteamAPI_SteamUserStats_v012())
+                {
+                    attempts += 1;
+                    return true;
+                }
+                if let Some(cb) = cb.take() {
+                    cb(v.result);
+                }
+                false
+            });
+        }
+    }
+
+    /// Send the changed stats and achievements data to the server and report what the
+    /// server accepted.
+    ///
+    /// Unlike [`store_stats()`](#method.store_stats), which only reports whether the
+    /// store could be queued, `cb` is called once
+    /// [`Client::run_callbacks`](../struct.Client.html#method.run_callbacks) has
+    /// processed the [`UserStatsStored`](../struct.UserStatsStored.html) callback. On
+    /// success it receives the API names from the
+    /// [`UserAchievementStored`](../struct.UserAchievementStored.html) callbacks, i.e. the
+    /// achievements unlocked by this store. Steam doesn't document whether those come
+    /// before or after `UserStatsStored`, so the ones processed by the same
+    /// `run_callbacks` call are included either way.
+    ///
+    /// `cb` receives an error right away if the stats couldn't be stored at all (e.g.
+    /// they haven't been received yet).
+    ///
+    /// Only have one store in flight at a time. Steam's callbacks carry nothing but the
+    /// game id, so the first `UserStatsStored` completes every pending call with its
+    /// result and all achievements stored up to then.
+    pub fn store_stats_async<F>(&self, cb: F)
+    where
+        F: FnOnce(Result<Vec<String>, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            // Held until the listeners are registered so no callback is missed
+            let mut callbacks = self.inner.callbacks.lock().unwrap();
+            if !sys::SteamAPI_ISteamUserStats_StoreStats(self.user_stats) {
+                drop(callbacks);
+                cb(Err(SteamError::Generic));
+                return;
+            }
+            let token = ListenerToken::new();
+            let achievements = Arc::new(Mutex::new(Vec::new()));
+            let achievements2 = achievements.clone();
+            add_listener(&mut callbacks, &token, move |v: UserAchievementStored| {
+                achievements2.lock().unwrap().push(v.achievement_name);
+                true
+            });
+            let after_dispatch = self.inner.after_dispatch.clone();
+            let token2 = token.clone();
+            let mut cb = Some(cb);
+            add_listener(&mut callbacks, &token, move |v: UserStatsStored| {
+                if let Some(cb) = cb.take() {
+                    let achievements = achievements.clone();
+                    let token = token2.clone();
+                    // Both listeners are removed once the result is delivered
+                    after_dispatch.lock().unwrap().push(Box::new(move || {
+                        token.remove();
+                        let achievements = std::mem::take(&mut *achievements.lock().unwrap());
+                        cb(v.result.map(|()| achievements));
+                    }));
+                }
+                true
+            });
         }
     }
 
@@ -89,8 +541,11 @@ impl<Manager> UserStats<Manager> {
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
     /// and a successful [`UserStatsReceived`](./struct.UserStatsReceived.html) callback processed.
-    pub fn get_stat_i32(&self, name: &str) -> Result<i32, ()> {
-        let name = CString::new(name).unwrap();
+    ///
+    /// The cause of a failure is inferred, see [`UserStatsError`](../enum.UserStatsError.html).
+    /// The same applies to the other stat getters and setters.
+    pub fn get_stat_i32(&self, name: &str) -> Result<i32, UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
 
         let mut value: i32 = 0;
         let success = unsafe {
@@ -103,7 +558,7 @@ impl<Manager> UserStats<Manager> {
         if success {
             Ok(value)
         } else {
-            Err(())
+            Err(self.stat_error(&name, true))
         }
     }
 
@@ -116,8 +571,8 @@ impl<Manager> UserStats<Manager> {
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
     /// and a successful [`UserStatsReceived`](./struct.UserStatsReceived.html) callback processed.
-    pub fn set_stat_i32(&self, name: &str, stat: i32) -> Result<(), ()> {
-        let name = CString::new(name).unwrap();
+    pub fn set_stat_i32(&self, name: &str, stat: i32) -> Result<(), UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
 
         let success = unsafe {
             sys::SteamAPI_ISteamUserStats_SetStatInt32(
@@ -129,7 +584,7 @@ impl<Manager> UserStats<Manager> {
         if success {
             Ok(())
         } else {
-            Err(())
+            Err(self.stat_error(&name, true))
         }
     }
 
@@ -139,8 +594,8 @@ impl<Manager> UserStats<Manager> {
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
     /// and a successful [`UserStatsReceived`](./struct.UserStatsReceived.html) callback processed.
-    pub fn get_stat_f32(&self, name: &str) -> Result<f32, ()> {
-        let name = CString::new(name).unwrap();
+    pub fn get_stat_f32(&self, name: &str) -> Result<f32, UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
 
         let mut value: f32 = 0.0;
         let success = unsafe {
@@ -153,7 +608,7 @@ impl<Manager> UserStats<Manager> {
         if success {
             Ok(value)
         } else {
-            Err(())
+            Err(self.stat_error(&name, false))
         }
     }
 
@@ -166,8 +621,8 @@ impl<Manager> UserStats<Manager> {
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
     /// and a successful [`UserStatsReceived`](./struct.UserStatsReceived.html) callback processed.
-    pub fn set_stat_f32(&self, name: &str, stat: f32) -> Result<(), ()> {
-        let name = CString::new(name).unwrap();
+    pub fn set_stat_f32(&self, name: &str, stat: f32) -> Result<(), UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
 
         let success = unsafe {
             sys::SteamAPI_ISteamUserStats_SetStatFloat(
@@ -179,10 +634,374 @@ impl<Manager> UserStats<Manager> {
         if success {
             Ok(())
         } else {
-            Err(())
+            Err(self.stat_error(&name, false))
+        }
+    }
+
+    /// Sets / updates the value of a given stat for the current user, clamping it to
+    /// `min..=max` first
+    ///
+    /// Steam rejects writes outside the bounds configured for the stat on the Steamworks
+    /// App Admin website, so pass the same bounds here. The server's configuration is
+    /// authoritative; this only avoids sending values it would refuse.
+    ///
+    /// See [`set_stat_f32()`](#method.set_stat_f32) for the other requirements.
+    pub fn set_stat_f32_clamped(
+        &self,
+        name: &str,
+        stat: f32,
+        min: f32,
+        max: f32,
+    ) -> Result<(), UserStatsError> {
+        self.set_stat_f32(name, stat.max(min).min(max))
+    }
+
+    /// Updates an average rate stat, e.g. the average speed, for the current user
+    ///
+    /// `count_this_session` is what is being averaged since the last call, e.g. the
+    /// distance travelled, and `session_length` the time it took in seconds. Steam
+    /// keeps the totals and computes the average, which is read back with
+    /// [`get_stat_f32()`](#method.get_stat_f32).
+    ///
+    /// This call only changes the value in-memory and is very cheap. To commit the stats you
+    /// must call [`store_stats()`](#method.store_stats)
+    ///
+    /// The specified stat must exist and be of the `AVGRATE` type on the Steamworks App Admin
+    /// website, other stats fail with [`UserStatsError::WrongType`].
+    pub fn update_avg_rate_stat(
+        &self,
+        name: &str,
+        count_this_session: f32,
+        session_length: f64,
+    ) -> Result<(), UserStatsError> {
+        let name = CString::new(name).map_err(|_| UserStatsError::InvalidName)?;
+
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_UpdateAvgRateStat(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                count_this_session,
+                session_length,
+            )
+        };
+        if success {
+            return Ok(());
+        }
+        // A float stat that isn't an average rate also refuses the update
+        let mut value = 0.0;
+        let is_float = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetStatFloat(self.user_stats, name.as_ptr(), &mut value)
+        };
+        if is_float && self.stats_loaded() {
+            Err(UserStatsError::WrongType)
+        } else {
+            Err(self.stat_error(&name, false))
         }
     }
 
+    /// Looks up a leaderboard by name.
+    ///
+    /// `cb` receives `Ok(None)` if no leaderboard with that name exists, and
+    /// [`SteamError::InvalidName`] if `name` contains a nul byte.
+    pub fn find_leaderboard<F>(&self, name: &str, cb: F)
+    where
+        F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
+    {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return cb(Err(SteamError::InvalidName)),
+        };
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUserStats_FindLeaderboard(
+                self.user_stats,
+                name.as_ptr() as *const _,
+            );
+            register_find_result(&self.inner, api_call, cb);
+        }
+    }
+
+    /// Looks up a leaderboard by name, creating it if it doesn't exist yet.
+    ///
+    /// `sort` and `display` are only used when creating the leaderboard.
+    /// `cb` receives the leaderboard like with
+    /// [`find_leaderboard()`](#method.find_leaderboard).
+    pub fn find_or_create_leaderboard<F>(
+        &self,
+        name: &str,
+        sort: LeaderboardSortMethod,
+        display: LeaderboardDisplayType,
+        cb: F,
+    ) where
+        F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
+    {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return cb(Err(SteamError::InvalidName)),
+        };
+        let sort = match sort {
+            LeaderboardSortMethod::Ascending => {
+                sys::ELeaderboardSortMethod::k_ELeaderboardSortMethodAscending
+            }
+            LeaderboardSortMethod::Descending => {
+                sys::ELeaderboardSortMethod::k_ELeaderboardSortMethodDescending
+            }
+        };
+        let display = match display {
+            LeaderboardDisplayType::Numeric => {
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeNumeric
+            }
+            LeaderboardDisplayType::TimeSeconds => {
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeTimeSeconds
+            }
+            LeaderboardDisplayType::TimeMilliSeconds => {
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeTimeMilliSeconds
+            }
+        };
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUserStats_FindOrCreateLeaderboard(
+                self.user_stats,
+                name.as_ptr() as *const _,
+                sort,
+                display,
+            );
+            register_find_result(&self.inner, api_call, cb);
+        }
+    }
+
+    /// Uploads a score for the current user to a leaderboard.
+    ///
+    /// `details` are stored with the score, at most
+    /// [`MAX_LEADERBOARD_DETAILS`](./constant.MAX_LEADERBOARD_DETAILS.html) of them.
+    pub fn upload_leaderboard_score<F>(
+        &self,
+        leaderboard: Leaderboard,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+        cb: F,
+    ) where
+        F: FnOnce(Result<LeaderboardScoreUploaded, SteamError>) + 'static + Send,
+    {
+        let method = match method {
+            UploadScoreMethod::KeepBest => {
+                sys::ELeaderboardUploadScoreMethod::k_ELeaderboardUploadScoreMethodKeepBest
+            }
+            UploadScoreMethod::ForceUpdate => {
+                sys::ELeaderboardUploadScoreMethod::k_ELeaderboardUploadScoreMethodForceUpdate
+            }
+        };
+        let details = &details[..details.len().min(MAX_LEADERBOARD_DETAILS)];
+        unsafe {
+            let api_call = sys::SteamAPI_ISteamUserStats_UploadLeaderboardScore(
+                self.user_stats,
+                leaderboard.0,
+                method,
+                score,
+                if details.is_empty() {
+                    std::ptr::null()
+                } else {
+                    details.as_ptr()
+                },
+                details.len() as i32,
+            );
+            register_call_result::<sys::LeaderboardScoreUploaded_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 6,
+                move |v, io_error| {
+                    cb(if io_error {
+                        Err(SteamError::IOFailure)
+                    } else if v.m_bSuccess == 0 {
+                        Err(SteamError::Generic)
+                    } else {
+                        Ok(LeaderboardScoreUploaded {
+                            score: v.m_nScore,
+                            was_changed: v.m_bScoreChanged != 0,
+                            global_rank_new: v.m_nGlobalRankNew,
+                            global_rank_previous: v.m_nGlobalRankPrevious,
+                        })
+                    })
+                },
+            );
+        }
+    }
+
+    /// Downloads entries of a leaderboard.
+    ///
+    /// `start` and `end` are inclusive and interpreted depending on `request`,
+    /// see [`LeaderboardDataRequest`](./enum.LeaderboardDataRequest.html). Up to
+    /// `max_details` of the details stored with each entry are returned with it.
+    pub fn download_leaderboard_entries<F>(
+        &self,
+        leaderboard: Leaderboard,
+        request: LeaderboardDataRequest,
+        start: i32,
+        end: i32,
+        max_details: usize,
+        cb: F,
+    ) where
+        F: FnOnce(Result<Vec<LeaderboardEntry>, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            download_entries(
+                &self.inner,
+                self.user_stats,
+                leaderboard,
+                request,
+                start,
+                end,
+                max_details,
+                cb,
+            );
+        }
+    }
+
+    /// Returns a pager over the global entries of `leaderboard`, `page_size`
+    /// entries at a time.
+    ///
+    /// Returns `None` if `page_size` isn't positive.
+    pub fn leaderboard_global_iter(
+        &self,
+        leaderboard: Leaderboard,
+        page_size: i32,
+    ) -> Option<LeaderboardPager<Manager>> {
+        if page_size <= 0 {
+            return None;
+        }
+        Some(LeaderboardPager {
+            user_stats: self.user_stats,
+            inner: self.inner.clone(),
+            leaderboard,
+            page_size,
+            offset: 0,
+        })
+    }
+
+    /// Returns the number of entries in `leaderboard`.
+    ///
+    /// Like the other leaderboard metadata this is available without a network request
+    /// once the leaderboard has been found. Returns `None` for a null handle.
+    pub fn leaderboard_entry_count(&self, leaderboard: &Leaderboard) -> Option<i32> {
+        if self.user_stats.is_null() || leaderboard.0 == 0 {
+            return None;
+        }
+        unsafe {
+            Some(sys::SteamAPI_ISteamUserStats_GetLeaderboardEntryCount(
+                self.user_stats,
+                leaderboard.0,
+            ))
+        }
+    }
+
+    /// Returns the name of `leaderboard`, `None` for a null handle.
+    pub fn leaderboard_name(&self, leaderboard: &Leaderboard) -> Option<String> {
+        if self.user_stats.is_null() || leaderboard.0 == 0 {
+            return None;
+        }
+        unsafe {
+            let name =
+                sys::SteamAPI_ISteamUserStats_GetLeaderboardName(self.user_stats, leaderboard.0);
+            if name.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr(name);
+            if name.is_empty() {
+                None
+            } else {
+                Some(name.to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Returns how `leaderboard` is sorted, `None` for a null handle.
+    pub fn leaderboard_sort_method(
+        &self,
+        leaderboard: &Leaderboard,
+    ) -> Option<LeaderboardSortMethod> {
+        if self.user_stats.is_null() || leaderboard.0 == 0 {
+            return None;
+        }
+        unsafe {
+            match sys::SteamAPI_ISteamUserStats_GetLeaderboardSortMethod(
+                self.user_stats,
+                leaderboard.0,
+            ) {
+                sys::ELeaderboardSortMethod::k_ELeaderboardSortMethodAscending => {
+                    Some(LeaderboardSortMethod::Ascending)
+                }
+                sys::ELeaderboardSortMethod::k_ELeaderboardSortMethodDescending => {
+                    Some(LeaderboardSortMethod::Descending)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Returns how the scores of `leaderboard` are displayed, `None` for a null handle.
+    pub fn leaderboard_display_type(
+        &self,
+        leaderboard: &Leaderboard,
+    ) -> Option<LeaderboardDisplayType> {
+        if self.user_stats.is_null() || leaderboard.0 == 0 {
+            return None;
+        }
+        unsafe {
+            match sys::SteamAPI_ISteamUserStats_GetLeaderboardDisplayType(
+                self.user_stats,
+                leaderboard.0,
+            ) {
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeNumeric => {
+                    Some(LeaderboardDisplayType::Numeric)
+                }
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeTimeSeconds => {
+                    Some(LeaderboardDisplayType::TimeSeconds)
+                }
+                sys::ELeaderboardDisplayType::k_ELeaderboardDisplayTypeTimeMilliSeconds => {
+                    Some(LeaderboardDisplayType::TimeMilliSeconds)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Works out why a stat call for `name` failed, `is_int` being whether it was
+    /// used as an integer stat.
+    ///
+    /// Steam only reports failure, so this reads the stat as the other type to tell
+    /// a type mismatch from a missing stat.
+    fn stat_error(&self, name: &CStr, is_int: bool) -> UserStatsError {
+        if !self.stats_loaded() {
+            return UserStatsError::StatsNotLoaded;
+        }
+        let other_type = unsafe {
+            if is_int {
+                let mut value = 0.0;
+                sys::SteamAPI_ISteamUserStats_GetStatFloat(
+                    self.user_stats,
+                    name.as_ptr(),
+                    &mut value,
+                )
+            } else {
+                let mut value = 0;
+                sys::SteamAPI_ISteamUserStats_GetStatInt32(
+                    self.user_stats,
+                    name.as_ptr(),
+                    &mut value,
+                )
+            }
+        };
+        if other_type {
+            UserStatsError::WrongType
+        } else {
+            UserStatsError::StatNotFound
+        }
+    }
+
+    /// Returns whether the current user's stats have been received from Steam.
+    pub(crate) fn stats_loaded(&self) -> bool {
+        self.inner.stats_loaded.load(Ordering::Acquire)
+    }
+
     /// Access achievement API for a given achievement 'API Name'.
     ///
     /// Requires [`request_current_stats()`](#method.request_current_stats) to have been called
@@ -201,41 +1020,44 @@ impl<Manager> UserStats<Manager> {
     /// This is used for iterating through all of the achievements with GetAchievementName.
     ///
     /// Returns 0 if the current App ID has no achievements.
-    /// 
-    /// *Note: Returns an error for AppId `480` (Spacewar)!*
-    pub fn get_num_achievements(&self) -> Result<u32,()> {
+    pub fn get_num_achievements(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamUserStats_GetNumAchievements(self.user_stats) }
+    }
+
+    /// Returns whether the current App ID defines any achievements.
+    pub fn has_achievements(&self) -> bool {
+        self.get_num_achievements() != 0
+    }
+
+    /// Returns the API name of the achievement at `index`.
+    ///
+    /// `index` should be less than [`get_num_achievements()`](#method.get_num_achievements).
+    /// Returns `None` if the index is out of range, instead of the empty string Steam reports.
+    pub fn achievement_name(&self, index: u32) -> Option<String> {
         unsafe {
-            let num = sys::SteamAPI_ISteamUserStats_GetNumAchievements(
-                self.user_stats,
-            );
-            if num != 0 {
-                Ok(num)
+            let name = sys::SteamAPI_ISteamUserStats_GetAchievementName(self.user_stats, index);
+            if name.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr(name);
+            if name.is_empty() {
+                None
             } else {
-                Err(())
+                Some(name.to_string_lossy().into_owned())
             }
         }
     }
 
-    /// Returns an array of all achievement names for the current AppId.
-    /// 
-    /// Returns an empty string for an achievement name if `iAchievement` is not a valid index,
-    /// and the current AppId must have achievements.
-    pub fn get_achievement_names(&self) -> Option<Vec<String>> {
-        let num = self.get_num_achievements().expect("Failed to get number of achievements");
-        let mut names = Vec::new();
-
-        for i in 0..num {
-            unsafe {
-                let name = sys::SteamAPI_ISteamUserStats_GetAchievementName(
-                    self.user_stats,
-                    i
-                );
-
-                let c_str = CStr::from_ptr(name).to_string_lossy().into_owned();
-
-                names.push(c_str);
-            }
+    /// Returns the API names of all achievements for the current AppId.
+    ///
+    /// Apps without achievements, like AppId `480` (Spacewar), get an empty list. Fails
+    /// with [`UserStatsError::StatsNotLoaded`](../enum.UserStatsError.html) if there are no
+    /// achievements because the stats haven't been received yet.
+    pub fn get_achievement_names(&self) -> Result<Vec<String>, UserStatsError> {
+        let num = self.get_num_achievements();
+        if num == 0 && !self.stats_loaded() {
+            return Err(UserStatsError::StatsNotLoaded);
         }
-        Some(names)
+        Ok((0..num).filter_map(|i| self.achievement_name(i)).collect())
     }
 }
@@ -0,0 +1,238 @@
+use super::*;
+
+/// The most detail values Steam stores per leaderboard entry
+pub const MAX_LEADERBOARD_DETAILS: usize = sys::k_cLeaderboardDetailsMax as usize;
+
+/// A handle to a steam leaderboard
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Leaderboard(pub(crate) u64);
+
+impl Leaderboard {
+    /// Creates a `Leaderboard` from a raw 64 bit handle.
+    pub fn from_raw(id: u64) -> Leaderboard {
+        Leaderboard(id)
+    }
+
+    /// Returns the raw 64 bit handle of the leaderboard
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+}
+
+/// How the entries of a leaderboard are ranked
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LeaderboardSortMethod {
+    /// The lowest score is ranked first
+    Ascending,
+    /// The highest score is ranked first
+    Descending,
+}
+
+/// How the scores of a leaderboard are displayed
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LeaderboardDisplayType {
+    /// A plain number
+    Numeric,
+    /// A time in seconds
+    TimeSeconds,
+    /// A time in milliseconds
+    TimeMilliSeconds,
+}
+
+/// A downloaded row of a leaderboard
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LeaderboardEntry {
+    pub steam_id: SteamId,
+    /// The rank of the entry, starting at 1
+    pub global_rank: i32,
+    pub score: i32,
+    /// The details uploaded with the score
+    pub details: Vec<i32>,
+}
+
+/// Which entries of a leaderboard to download
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LeaderboardDataRequest {
+    /// Entries by global rank, the range is a range of ranks starting at 1
+    Global,
+    /// Entries around the current user's, the range is relative to them, e.g.
+    /// `-4..=5` for the 4 entries before and the 5 after
+    GlobalAroundUser,
+    /// The entries of the current user's friends, the range is ignored
+    Friends,
+}
+
+/// How an uploaded score is combined with the user's existing entry
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UploadScoreMethod {
+    /// Only replace the existing score if the new one is better
+    KeepBest,
+    /// Always replace the existing score
+    ForceUpdate,
+}
+
+/// The result of uploading a leaderboard score
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LeaderboardScoreUploaded {
+    pub score: i32,
+    /// Whether the user's entry changed, e.g. `false` for a worse score with
+    /// [`UploadScoreMethod::KeepBest`]
+    pub was_changed: bool,
+    /// The user's rank after the upload
+    pub global_rank_new: i32,
+    /// The user's rank before the upload, `0` if they had no entry
+    pub global_rank_previous: i32,
+}
+
+/// Registers the handler for a `LeaderboardFindResult_t` call result
+pub(crate) unsafe fn register_find_result<Manager, F>(
+    inner: &Arc<Inner<Manager>>,
+    api_call: sys::SteamAPICall_t,
+    cb: F,
+) where
+    F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
+{
+    register_call_result::<sys::LeaderboardFindResult_t, _, _>(
+        inner,
+        api_call,
+        CALLBACK_BASE_ID + 4,
+        move |v, io_error| {
+            cb(if io_error {
+                Err(SteamError::IOFailure)
+            } else if v.m_bLeaderboardFound == 0 {
+                Ok(None)
+            } else {
+                Ok(Some(Leaderboard(v.m_hSteamLeaderboard)))
+            })
+        },
+    );
+}
+
+/// Downloads a range of leaderboard entries and decodes them
+#[allow(clippy::too_many_arguments)]
+pub(crate) unsafe fn download_entries<Manager, F>(
+    inner: &Arc<Inner<Manager>>,
+    user_stats: *mut sys::ISteamUserStats,
+    leaderboard: Leaderboard,
+    request: LeaderboardDataRequest,
+    start: i32,
+    end: i32,
+    max_details: usize,
+    cb: F,
+) where
+    F: FnOnce(Result<Vec<LeaderboardEntry>, SteamError>) + 'static + Send,
+{
+    let max_details = max_details.min(MAX_LEADERBOARD_DETAILS);
+    let request = match request {
+        LeaderboardDataRequest::Global => {
+            sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobal
+        }
+        LeaderboardDataRequest::GlobalAroundUser => {
+            sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobalAroundUser
+        }
+        LeaderboardDataRequest::Friends => {
+            sys::ELeaderboardDataRequest::k_ELeaderboardDataRequestFriends
+        }
+    };
+    let api_call = sys::SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+        user_stats,
+        leaderboard.0,
+        request,
+        start,
+        end,
+    );
+    register_call_result::<sys::LeaderboardScoresDownloaded_t, _, _>(
+        inner,
+        api_call,
+        CALLBACK_BASE_ID + 5,
+        move |v, io_error| {
+            if io_error {
+                cb(Err(SteamError::IOFailure));
+                return;
+            }
+            let user_stats = sys::SteamAPI_SteamUserStats_v012();
+            let mut entries = Vec::with_capacity(v.m_cEntryCount.max(0) as usize);
+            for index in 0..v.m_cEntryCount {
+                let mut entry: sys::LeaderboardEntry_t = std::mem::zeroed();
+                let mut details = vec![0; max_details];
+                if sys::SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+                    user_stats,
+                    v.m_hSteamLeaderboardEntries,
+                    index,
+                    &mut entry,
+                    details.as_mut_ptr(),
+                    max_details as i32,
+                ) {
+                    details.truncate((entry.m_cDetails.max(0) as usize).min(max_details));
+                    entries.push(LeaderboardEntry {
+                        steam_id: SteamId(entry.m_steamIDUser.m_steamid.m_unAll64Bits),
+                        global_rank: entry.m_nGlobalRank,
+                        score: entry.m_nScore,
+                        details,
+                    });
+                }
+            }
+            cb(Ok(entries))
+        },
+    );
+}
+
+/// Pages through the global entries of a leaderboard
+///
+/// Created by
+/// [`UserStats::leaderboard_global_iter`](struct.UserStats.html#method.leaderboard_global_iter).
+pub struct LeaderboardPager<Manager> {
+    pub(crate) user_stats: *mut sys::ISteamUserStats,
+    pub(crate) inner: Arc<Inner<Manager>>,
+    pub(crate) leaderboard: Leaderboard,
+    pub(crate) page_size: i32,
+    pub(crate) offset: i32,
+}
+
+impl<Manager> LeaderboardPager<Manager> {
+    /// Requests the next page of entries.
+    ///
+    /// Returns `false` without calling `cb` once every entry of the
+    /// leaderboard has been requested. The offset advances as soon as a page
+    /// is requested, so several pages can be in flight at once.
+    pub fn next_page<F>(&mut self, cb: F) -> bool
+    where
+        F: FnOnce(Result<Vec<LeaderboardEntry>, SteamError>) + 'static + Send,
+    {
+        unsafe {
+            let count = sys::SteamAPI_ISteamUserStats_GetLeaderboardEntryCount(
+                self.user_stats,
+                self.leaderboard.0,
+            );
+            if self.offset >= count {
+                return false;
+            }
+            let start = self.offset + 1;
+            let end = self.offset.saturating_add(self.page_size).min(count);
+            self.offset = end;
+            download_entries(
+                &self.inner,
+                self.user_stats,
+                self.leaderboard,
+                LeaderboardDataRequest::Global,
+                start,
+                end,
+                MAX_LEADERBOARD_DETAILS,
+                cb,
+            );
+            true
+        }
+    }
+
+    /// Returns how many entries have been requested so far.
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+}
@@ -7,7 +7,7 @@ use super::*;
 ///
 /// ```no_run
 /// # use steamworks::*;
-/// # let (client, single) = steamworks::Client::init().unwrap();
+/// # let client = steamworks::Client::init().unwrap();
 /// let callback_handle = client.register_callback(|val: UserStatsReceived| {
 ///     if val.result.is_err() {
 ///         // ...
@@ -44,7 +44,7 @@ unsafe impl Callback for UserStatsReceived {
 ///
 /// ```no_run
 /// # use steamworks::*;
-/// # let (client, single) = steamworks::Client::init().unwrap();
+/// # let client = steamworks::Client::init().unwrap();
 /// let callback_handle = client.register_callback(|val: UserStatsStored| {
 ///     if val.result.is_err() {
 ///         // ...
@@ -81,7 +81,7 @@ unsafe impl Callback for UserStatsStored {
 ///
 /// ```no_run
 /// # use steamworks::*;
-/// # let (client, single) = steamworks::Client::init().unwrap();
+/// # let client = steamworks::Client::init().unwrap();
 /// let callback_handle = client.register_callback(|val: UserAchievementStored| {
 ///     // ...
 /// });
@@ -111,3 +111,43 @@ unsafe impl Callback for UserAchievementStored {
         }
     }
 }
+
+/// Callback sent when an achievement icon has finished loading.
+///
+/// [`get_achievement_icon()`](stats/struct.AchievementHelper.html#method.get_achievement_icon)
+/// returns `None` until then, so this can be used to fill in icons as they arrive.
+///
+/// # Example
+///
+/// ```no_run
+/// # use steamworks::*;
+/// # let client = steamworks::Client::init().unwrap();
+/// let callback_handle = client.register_callback(|val: UserAchievementIconFetched| {
+///     // ...
+/// });
+/// ```
+#[derive(Debug)]
+pub struct UserAchievementIconFetched {
+    pub game_id: GameId,
+    pub achievement_name: String,
+    /// Whether the icon is the achieved or the unachieved version.
+    pub achieved: bool,
+    /// The handle of the icon, usable with the steam utils image functions.
+    pub icon_handle: i32,
+}
+
+unsafe impl Callback for UserAchievementIconFetched {
+    const ID: i32 = CALLBACK_BASE_ID + 9;
+    const SIZE: i32 = std::mem::size_of::<sys::UserAchievementIconFetched_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::UserAchievementIconFetched_t);
+        let name = CStr::from_ptr(val.m_rgchAchievementName.as_ptr());
+        Self {
+            game_id: GameId(val.m_nGameID.__bindgen_anon_1.m_ulGameID),
+            achievement_name: name.to_string_lossy().into_owned(),
+            achieved: val.m_bAchieved,
+            icon_handle: val.m_nIconHandle,
+        }
+    }
+}
@@ -52,6 +52,53 @@ impl<M> AchievementHelper<'_, M> {
         }
     }
 
+    /// Returns whether the current user has unlocked the achievement.
+    ///
+    /// Unlike [`get()`](#method.get) a failure reports why the state couldn't be read,
+    /// so a locked achievement (`Ok(false)`) can be told apart from stats that haven't
+    /// been received yet.
+    pub fn is_achieved(&self) -> Result<bool, UserStatsError> {
+        let mut achieved = false;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetAchievement(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                &mut achieved,
+            )
+        };
+        if success {
+            Ok(achieved)
+        } else if !self.parent.stats_loaded() {
+            Err(UserStatsError::StatsNotLoaded)
+        } else {
+            Err(UserStatsError::AchievementNotFound)
+        }
+    }
+
+    /// Gets the unlock status of the achievement and the time it was unlocked.
+    ///
+    /// The time is a Unix timestamp in seconds, and `0` if the achievement
+    /// hasn't been unlocked.
+    ///
+    /// Returns `None` if this achievement's 'API Name' is unknown, or unsuccessful
+    /// [`UserStatsReceived`](../struct.UserStatsReceived.html).
+    pub fn get_achievement_and_unlock_time(&self) -> Option<(bool, u32)> {
+        let mut achieved = false;
+        let mut unlock_time = 0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetAchievementAndUnlockTime(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                &mut achieved,
+                &mut unlock_time,
+            )
+        };
+        if !success {
+            return None;
+        }
+        Some((achieved, if achieved { unlock_time } else { 0 }))
+    }
+
     /// Unlocks an achievement.
     ///
     /// This call only modifies Steam's in-memory state so it is quite cheap. To send the unlock
@@ -76,6 +123,9 @@ impl<M> AchievementHelper<'_, M> {
 
     /// Resets the unlock status of an achievement.
     ///
+    /// Mainly useful while testing, or for achievement-like states the game can take away
+    /// again.
+    ///
     /// This call only modifies Steam's in-memory state so it is quite cheap. To send the unlock
     /// status to the server and to trigger the Steam overlay notification you must call
     /// [`store_stats()`](../struct.UserStats.html#method.store_stats).
@@ -95,7 +145,75 @@ impl<M> AchievementHelper<'_, M> {
             Err(())
         }
     }
-    
+
+    /// Shows the Steam progress notification for a progress based achievement, e.g.
+    /// "50/100", without unlocking it.
+    ///
+    /// Unlike [`set()`](#method.set) the notification appears without calling
+    /// [`store_stats()`](../struct.UserStats.html#method.store_stats). A
+    /// [`UserAchievementStored`](../struct.UserAchievementStored.html) callback is sent
+    /// once it has been shown.
+    ///
+    /// Returns `false` if the achievement is already unlocked, this achievement's
+    /// 'API Name' is unknown, or unsuccessful
+    /// [`UserStatsReceived`](../struct.UserStatsReceived.html).
+    pub fn indicate_progress(&self, current: u32, max: u32) -> bool {
+        unsafe {
+            sys::SteamAPI_ISteamUserStats_IndicateAchievementProgress(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                current,
+                max,
+            )
+        }
+    }
+
+    /// Gets the progress range of an achievement backed by an integer stat, as `(min, max)`.
+    ///
+    /// Useful to render a progress bar next to
+    /// [`indicate_progress()`](#method.indicate_progress). Returns `None` if the achievement
+    /// isn't a progress achievement, or its stat isn't an integer.
+    pub fn get_progress_limits_i32(&self) -> Option<(i32, i32)> {
+        let mut min = 0;
+        let mut max = 0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetAchievementProgressLimitsInt32(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                &mut min,
+                &mut max,
+            )
+        };
+        if success {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the progress range of an achievement backed by a float stat, as `(min, max)`.
+    ///
+    /// Useful to render a progress bar next to
+    /// [`indicate_progress()`](#method.indicate_progress). Returns `None` if the achievement
+    /// isn't a progress achievement, or its stat isn't a float.
+    pub fn get_progress_limits_f32(&self) -> Option<(f32, f32)> {
+        let mut min = 0.0;
+        let mut max = 0.0;
+        let success = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetAchievementProgressLimitsFloat(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                &mut min,
+                &mut max,
+            )
+        };
+        if success {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+
     /// Returns the percentage of users who have unlocked the specified achievement.
     /// 
     /// You must have called `request_global_achievement_percentages()` and it needs to return
@@ -191,6 +309,61 @@ impl<M> AchievementHelper<'_, M> {
         }
     }
 
+    /// Gets a display attribute of the achievement, see
+    /// [`get_achievement_display_attribute()`](#method.get_achievement_display_attribute)
+    /// for the supported keys.
+    ///
+    /// Returns `None` where Steam returns an empty string, e.g. for an unknown key or before
+    /// the stats have been received.
+    pub fn get_display_attribute(&self, key: &str) -> Option<String> {
+        let key = CString::new(key).ok()?;
+        unsafe {
+            let value = sys::SteamAPI_ISteamUserStats_GetAchievementDisplayAttribute(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+                key.as_ptr(),
+            );
+            if value.is_null() {
+                return None;
+            }
+            let value = CStr::from_ptr(value);
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Gets the localized name of the achievement.
+    pub fn name(&self) -> Option<String> {
+        self.get_display_attribute("name")
+    }
+
+    /// Gets the localized description of the achievement.
+    pub fn description(&self) -> Option<String> {
+        self.get_display_attribute("desc")
+    }
+
+    /// Gets whether the achievement is hidden until it is unlocked.
+    pub fn hidden(&self) -> Option<bool> {
+        match self.get_display_attribute("hidden")?.as_str() {
+            "0" => Some(false),
+            "1" => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Gets the icon for an achievement as `(width, height, rgba)`.
+    ///
+    /// Icons are loaded asynchronously. While the icon isn't loaded yet this returns `None`,
+    /// and a [`UserAchievementIconFetched`](../struct.UserAchievementIconFetched.html)
+    /// callback is sent once it is, after which this can be called again.
+    pub fn get_icon(&self) -> Option<(u32, u32, Vec<u8>)> {
+        self.get_achievement_icon()
+            .map(|icon| (icon.width, icon.height, icon.handle))
+    }
+
     /// Gets the icon for an achievement.
     /// 
     /// The image is returned as a handle to be used with `ISteamUtils::GetImageRGBA` to get
@@ -199,39 +372,24 @@ impl<M> AchievementHelper<'_, M> {
     /// **Note: This is handled within the function. Returns a `Vec<u8>` buffer on success,
     /// which can be converted into the image data and saved to disk (e.g. via external
     /// "RGBA to image" crate).*
+    ///
+    /// Icons are loaded asynchronously, so this returns `None` until Steam has loaded the
+    /// icon. Call it again later to get it.
+    ///
+    /// Icons are defined per app, not per user. Steam returns the achieved or unachieved
+    /// icon depending on the *current* user's progress, so comparison screens should use
+    /// [`get_user_achievement()`](../struct.UserStats.html#method.get_user_achievement)
+    /// for the other user's status and pick the presentation (e.g. greyed out) from that.
     pub fn get_achievement_icon(&self) -> Option<AchievementIcon> {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
             let img = sys::SteamAPI_ISteamUserStats_GetAchievementIcon(
                 self.parent.user_stats,
                 self.name.as_ptr() as *const _,
             );
-            if img == 0 {
-                return None
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(
-                utils, 
-                img, 
-                &mut width, 
-                &mut height
-            ) {
-                return None
-            }
-            let mut dest = vec![0; (width as usize) * (height as usize) * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(
-                utils,
-                img,
-                dest.as_mut_ptr(),
-                (width * height * 4) as i32
-                ) {
-                return None
-            }
-            Some(AchievementIcon {
-                handle: dest,
-                width,
-                height
+            Image::from_handle(img).map(|image| AchievementIcon {
+                handle: image.data,
+                width: image.width,
+                height: image.height,
             })
         }
     }
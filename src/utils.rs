@@ -4,7 +4,7 @@ use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::panic;
 use std::process::abort;
-use std::sync::RwLock;
+use std::sync::{RwLock, Weak};
 
 /// Access to the steam utils interface
 pub struct Utils<Manager> {
@@ -12,23 +12,39 @@ pub struct Utils<Manager> {
     pub(crate) _inner: Arc<Inner<Manager>>,
 }
 
+const CALLBACK_BASE_ID: i32 = 700;
+
+/// A client's warning callback, stored in its `Inner`
+pub(crate) type WarningCallback = RwLock<Option<Box<dyn Fn(i32, &CStr) + Send + Sync>>>;
+
 lazy_static! {
-    /// Global rust warning callback
-    static ref WARNING_CALLBACK: RwLock<Option<Box<dyn Fn(i32, &CStr) + Send + Sync>>> = RwLock::new(None);
+    /// The warning callbacks of every client that set one
+    static ref WARNING_CALLBACKS: Mutex<Vec<Weak<WarningCallback>>> = Mutex::new(Vec::new());
 }
 
-/// C function to pass as the real callback, which forwards to the `WARNING_CALLBACK` if any
+/// C function to pass as the real callback, which forwards each warning to
+/// the callbacks of all clients still alive
 unsafe extern "C" fn c_warning_callback(level: i32, msg: *const c_char) {
-    let lock = WARNING_CALLBACK.read().expect("warning func lock poisoned");
-    let cb = match lock.as_ref() {
-        Some(cb) => cb,
-        None => {
-            return;
-        }
+    let callbacks: Vec<_> = {
+        let mut lock = WARNING_CALLBACKS
+            .lock()
+            .expect("warning func lock poisoned");
+        lock.retain(|cb| cb.strong_count() > 0);
+        lock.iter().filter_map(Weak::upgrade).collect()
     };
 
     let s = CStr::from_ptr(msg);
 
+    for cb in callbacks {
+        let lock = cb.read().expect("warning func lock poisoned");
+        if let Some(cb) = lock.as_ref() {
+            forward_warning(cb, level, s);
+        }
+    }
+}
+
+/// Calls a warning callback, aborting if it panics as it is called from C
+fn forward_warning(cb: &(dyn Fn(i32, &CStr) + Send + Sync), level: i32, s: &CStr) {
     let res = panic::catch_unwind(panic::AssertUnwindSafe(|| cb(level, s)));
     if let Err(err) = res {
         if let Some(err) = err.downcast_ref::<&str>() {
@@ -42,6 +58,153 @@ unsafe extern "C" fn c_warning_callback(level: i32, msg: *const c_char) {
     }
 }
 
+/// The corner of the screen steam overlay notifications are shown in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NotificationPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    /// Steam's default
+    #[default]
+    BottomRight,
+}
+
+impl NotificationPosition {
+    /// Picks the corner needing the smallest inset to stay clear of a HUD
+    /// that covers `top`, `right`, `bottom` and `left` pixels of the screen
+    /// edges, returning it with the horizontal and vertical inset to use.
+    ///
+    /// Ties go to the default corner, then top left, top right and bottom left.
+    pub fn for_safe_area(top: i32, right: i32, bottom: i32, left: i32) -> (Self, i32, i32) {
+        [
+            (NotificationPosition::BottomRight, right, bottom),
+            (NotificationPosition::TopLeft, left, top),
+            (NotificationPosition::TopRight, right, top),
+            (NotificationPosition::BottomLeft, left, bottom),
+        ]
+        .into_iter()
+        .min_by_key(|&(_, horizontal, vertical)| horizontal.max(0) + vertical.max(0))
+        .map(|(position, horizontal, vertical)| (position, horizontal.max(0), vertical.max(0)))
+        .unwrap()
+    }
+}
+
+/// An image loaded from Steam in RGBA format
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels, 4 bytes per pixel in row order
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// Reads the image behind a steam image handle.
+    ///
+    /// Returns `None` if the handle is invalid or the image isn't loaded yet.
+    pub(crate) unsafe fn from_handle(handle: i32) -> Option<Image> {
+        if handle <= 0 {
+            return None;
+        }
+        let utils = sys::SteamAPI_SteamUtils_v010();
+        let mut width = 0;
+        let mut height = 0;
+        if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, handle, &mut width, &mut height) {
+            return None;
+        }
+        let size = width as usize * height as usize * 4;
+        let mut data = vec![0; size];
+        if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, handle, data.as_mut_ptr(), size as i32) {
+            return None;
+        }
+        Some(Image {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+/// How the text entered with [`Utils::show_gamepad_text_input`] is shown
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GamepadTextInputMode {
+    Normal,
+    /// The text is hidden while typing
+    Password,
+}
+
+/// Whether [`Utils::show_gamepad_text_input`] accepts more than one line
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GamepadTextInputLineMode {
+    SingleLine,
+    MultipleLines,
+}
+
+/// Callback sent when the gamepad text input opened by
+/// [`Utils::show_gamepad_text_input`] is closed
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GamepadTextInputDismissed {
+    /// `false` if the user cancelled the input
+    pub submitted: bool,
+    /// The length of the submitted text in bytes
+    pub submitted_text_len: u32,
+}
+
+unsafe impl Callback for GamepadTextInputDismissed {
+    const ID: i32 = CALLBACK_BASE_ID + 14;
+    const SIZE: i32 = std::mem::size_of::<sys::GamepadTextInputDismissed_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GamepadTextInputDismissed_t);
+        GamepadTextInputDismissed {
+            submitted: val.m_bSubmitted,
+            submitted_text_len: val.m_unSubmittedText,
+        }
+    }
+}
+
+/// The kind of keyboard opened by [`Utils::show_floating_gamepad_text_input`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FloatingGamepadTextInputMode {
+    SingleLine,
+    MultipleLines,
+    Email,
+    Numeric,
+}
+
+/// Callback sent when the keyboard opened by
+/// [`Utils::show_floating_gamepad_text_input`] is closed
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FloatingGamepadTextInputDismissed;
+
+unsafe impl Callback for FloatingGamepadTextInputDismissed {
+    const ID: i32 = CALLBACK_BASE_ID + 38;
+    const SIZE: i32 = std::mem::size_of::<sys::FloatingGamepadTextInputDismissed_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        FloatingGamepadTextInputDismissed
+    }
+}
+
+/// Where text passed to [`Utils::filter_text`] comes from
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextFilteringContext {
+    Unknown,
+    /// Content created by players, e.g. a level name
+    GameContent,
+    /// A chat message from another player
+    Chat,
+    /// The name of a player or something they own
+    Name,
+}
+
 impl<Manager> Utils<Manager> {
     /// Returns the app ID of the current process
     pub fn app_id(&self) -> AppId {
@@ -60,7 +223,7 @@ impl<Manager> Utils<Manager> {
     /// Returns the language the steam client is currently
     /// running in.
     ///
-    /// Generally you want `Apps::current_game_language` instead of this
+    /// Generally you want [`Apps::current_game_language`] instead of this
     pub fn ui_language(&self) -> String {
         unsafe {
             let lang = sys::SteamAPI_ISteamUtils_GetSteamUILanguage(self.utils);
@@ -80,22 +243,351 @@ impl<Manager> Utils<Manager> {
     /// The passed-in function takes two arguments: a severity level (0 = info, 1 = warning) and
     /// the message itself.
     ///
+    /// The callback belongs to this client and replaces any callback it set
+    /// before. Other clients in the process keep their own callbacks. Steam
+    /// doesn't say which client a warning is about and all clients share the
+    /// same utils interface, so every warning is passed to the callbacks of
+    /// all clients that set one.
+    ///
     /// See [Steamwork's debugging page](https://partner.steamgames.com/doc/sdk/api/debugging) for more info.
     pub fn set_warning_callback<F>(&self, cb: F)
     where
         F: Fn(i32, &CStr) + Send + Sync + 'static,
     {
-        let mut lock = WARNING_CALLBACK
-            .write()
-            .expect("warning func lock poisoned");
-        *lock = Some(Box::new(cb));
+        let slot = &self._inner.warning_callback;
+        *slot.write().expect("warning func lock poisoned") = Some(Box::new(cb));
+        {
+            let mut lock = WARNING_CALLBACKS
+                .lock()
+                .expect("warning func lock poisoned");
+            lock.retain(|cb| cb.strong_count() > 0);
+            if !lock.iter().any(|cb| cb.as_ptr() == Arc::as_ptr(slot)) {
+                lock.push(Arc::downgrade(slot));
+            }
+        }
         unsafe {
             sys::SteamAPI_ISteamUtils_SetWarningMessageHook(self.utils, Some(c_warning_callback));
         }
     }
 
+    /// Sets the corner of the screen overlay notifications are shown in.
+    pub fn set_overlay_notification_position(&self, position: NotificationPosition) {
+        let position = match position {
+            NotificationPosition::TopLeft => sys::ENotificationPosition::k_EPositionTopLeft,
+            NotificationPosition::TopRight => sys::ENotificationPosition::k_EPositionTopRight,
+            NotificationPosition::BottomLeft => sys::ENotificationPosition::k_EPositionBottomLeft,
+            NotificationPosition::BottomRight => sys::ENotificationPosition::k_EPositionBottomRight,
+        };
+        unsafe {
+            sys::SteamAPI_ISteamUtils_SetOverlayNotificationPosition(self.utils, position);
+        }
+    }
+
+    /// Sets how far from the chosen corner overlay notifications are shown, in pixels.
+    ///
+    /// Use this with [`set_overlay_notification_position`](#method.set_overlay_notification_position)
+    /// to move achievement toasts out of the way of HUD elements near the corner.
+    pub fn set_overlay_notification_inset(&self, horizontal: i32, vertical: i32) {
+        unsafe {
+            sys::SteamAPI_ISteamUtils_SetOverlayNotificationInset(self.utils, horizontal, vertical);
+        }
+    }
+
+    /// Places overlay notifications so they stay clear of the game's own UI.
+    ///
+    /// The arguments are how many pixels the HUD covers along each screen edge.
+    /// The corner needing the smallest inset is picked, see
+    /// [`NotificationPosition::for_safe_area`].
+    pub fn set_overlay_safe_area(&self, top: i32, right: i32, bottom: i32, left: i32) {
+        let (position, horizontal, vertical) =
+            NotificationPosition::for_safe_area(top, right, bottom, left);
+        self.set_overlay_notification_position(position);
+        self.set_overlay_notification_inset(horizontal, vertical);
+    }
+
+    /// Returns whether the steam overlay is enabled and has hooked into the
+    /// game's rendering.
+    ///
+    /// The overlay can take a few seconds to start, so this may only become
+    /// `true` some time after initialization. While it is `false` overlay
+    /// features like invites, store and web pages won't open, so games should
+    /// fall back to their own UI.
+    pub fn is_overlay_enabled(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsOverlayEnabled(self.utils) }
+    }
+
+    /// Returns whether the overlay needs a frame to be presented even if
+    /// nothing else changed.
+    ///
+    /// Games that skip presenting frames, e.g. when paused, must still present
+    /// while this returns `true`, otherwise the overlay freezes.
+    pub fn overlay_needs_present(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_BOverlayNeedsPresent(self.utils) }
+    }
+
+    /// Opens the big picture gamepad text input, for entering text without a
+    /// keyboard in big picture or Steam Deck gaming mode.
+    ///
+    /// `max_chars` limits the length of the text and `existing` is shown as the
+    /// initial text. Once the input is closed `cb` receives the entered text,
+    /// or `None` if the user cancelled. Returns `false` without calling `cb` if
+    /// the input couldn't be opened, e.g. when not in big picture mode, another
+    /// one is still open or a string contains a nul byte.
+    pub fn show_gamepad_text_input<F>(
+        &self,
+        mode: GamepadTextInputMode,
+        line_mode: GamepadTextInputLineMode,
+        description: &str,
+        max_chars: u32,
+        existing: Option<&str>,
+        cb: F,
+    ) -> bool
+    where
+        F: FnOnce(Option<String>) + 'static + Send,
+    {
+        let mode = match mode {
+            GamepadTextInputMode::Normal => {
+                sys::EGamepadTextInputMode::k_EGamepadTextInputModeNormal
+            }
+            GamepadTextInputMode::Password => {
+                sys::EGamepadTextInputMode::k_EGamepadTextInputModePassword
+            }
+        };
+        let line_mode = match line_mode {
+            GamepadTextInputLineMode::SingleLine => {
+                sys::EGamepadTextInputLineMode::k_EGamepadTextInputLineModeSingleLine
+            }
+            GamepadTextInputLineMode::MultipleLines => {
+                sys::EGamepadTextInputLineMode::k_EGamepadTextInputLineModeMultipleLines
+            }
+        };
+        let (description, existing) = match (
+            CString::new(description),
+            CString::new(existing.unwrap_or("")),
+        ) {
+            (Ok(description), Ok(existing)) => (description, existing),
+            _ => return false,
+        };
+        // Steam only reports the dismissal, not which input it belongs to
+        let pending = self._inner.gamepad_text_input_pending.clone();
+        if pending.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        unsafe {
+            if !sys::SteamAPI_ISteamUtils_ShowGamepadTextInput(
+                self.utils,
+                mode,
+                line_mode,
+                description.as_ptr(),
+                max_chars,
+                existing.as_ptr(),
+            ) {
+                pending.store(false, Ordering::Release);
+                return false;
+            }
+            let mut cb = Some(cb);
+            register_listener(&self._inner, move |v: GamepadTextInputDismissed| {
+                pending.store(false, Ordering::Release);
+                let text = if v.submitted {
+                    let utils = sys::SteamAPI_SteamUtils_v010();
+                    let len = sys::SteamAPI_ISteamUtils_GetEnteredGamepadTextLength(utils);
+                    // The length excludes the nul terminator
+                    let mut text = vec![0 as c_char; len as usize + 1];
+                    if sys::SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(
+                        utils,
+                        text.as_mut_ptr(),
+                        text.len() as u32,
+                    ) {
+                        Some(CStr::from_ptr(text.as_ptr()).to_string_lossy().into_owned())
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                if let Some(cb) = cb.take() {
+                    cb(text);
+                }
+                false
+            });
+        }
+        true
+    }
+
+    /// Opens the Steam Deck's on-screen keyboard next to a text field of the
+    /// game.
+    ///
+    /// The rect of the text field is given in screen coordinates so the
+    /// keyboard doesn't cover it. Unlike
+    /// [`show_gamepad_text_input`](#method.show_gamepad_text_input) the text
+    /// arrives through the usual keyboard events of the OS. A
+    /// [`FloatingGamepadTextInputDismissed`] callback is sent once the keyboard
+    /// is closed. Returns `false` if the keyboard couldn't be opened.
+    pub fn show_floating_gamepad_text_input(
+        &self,
+        mode: FloatingGamepadTextInputMode,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> bool {
+        let mode = match mode {
+            FloatingGamepadTextInputMode::SingleLine => {
+                sys::EFloatingGamepadTextInputMode::k_EFloatingGamepadTextInputModeModeSingleLine
+            }
+            FloatingGamepadTextInputMode::MultipleLines => {
+                sys::EFloatingGamepadTextInputMode::k_EFloatingGamepadTextInputModeModeMultipleLines
+            }
+            FloatingGamepadTextInputMode::Email => {
+                sys::EFloatingGamepadTextInputMode::k_EFloatingGamepadTextInputModeModeEmail
+            }
+            FloatingGamepadTextInputMode::Numeric => {
+                sys::EFloatingGamepadTextInputMode::k_EFloatingGamepadTextInputModeModeNumeric
+            }
+        };
+        unsafe {
+            sys::SteamAPI_ISteamUtils_ShowFloatingGamepadTextInput(
+                self.utils, mode, x, y, width, height,
+            )
+        }
+    }
+
+    /// Closes the keyboard opened by
+    /// [`show_floating_gamepad_text_input`](#method.show_floating_gamepad_text_input).
+    pub fn dismiss_floating_gamepad_text_input(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_DismissFloatingGamepadTextInput(self.utils) }
+    }
+
+    /// Returns the battery level in percent, `None` when running on AC power.
+    pub fn get_current_battery_power(&self) -> Option<u8> {
+        match unsafe { sys::SteamAPI_ISteamUtils_GetCurrentBatteryPower(self.utils) } {
+            255 => None,
+            power => Some(power),
+        }
+    }
+
+    /// Loads the text filter needed by [`filter_text`](#method.filter_text).
+    ///
+    /// Returns `false` if filtering isn't available, in which case text is
+    /// passed through unfiltered.
+    pub fn init_filter_text(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_InitFilterText(self.utils, 0) }
+    }
+
+    /// Filters profanity and legally required content out of `text`
+    /// according to the current user's settings.
+    ///
+    /// `source_user` is the user that wrote the text, whose text isn't
+    /// filtered if it is the current user. Call
+    /// [`init_filter_text`](#method.init_filter_text) once first.
+    ///
+    /// Returns `None` if `text` contains a nul byte or Steam couldn't filter it.
+    pub fn filter_text(
+        &self,
+        context: TextFilteringContext,
+        source_user: SteamId,
+        text: &str,
+    ) -> Option<String> {
+        let context = match context {
+            TextFilteringContext::Unknown => {
+                sys::ETextFilteringContext::k_ETextFilteringContextUnknown
+            }
+            TextFilteringContext::GameContent => {
+                sys::ETextFilteringContext::k_ETextFilteringContextGameContent
+            }
+            TextFilteringContext::Chat => sys::ETextFilteringContext::k_ETextFilteringContextChat,
+            TextFilteringContext::Name => sys::ETextFilteringContext::k_ETextFilteringContextName,
+        };
+        let input = CString::new(text).ok()?;
+        // Steam needs room for the input and the nul terminator, the rest is slack
+        let mut output = vec![0 as c_char; text.len() * 2 + 1];
+        unsafe {
+            // The number of characters Steam replaced, anything negative is a failure
+            let filtered = sys::SteamAPI_ISteamUtils_FilterText(
+                self.utils,
+                context,
+                source_user.0,
+                input.as_ptr(),
+                output.as_mut_ptr(),
+                output.len() as u32,
+            );
+            match filtered {
+                0 => Some(text.to_owned()),
+                1.. => Some(
+                    CStr::from_ptr(output.as_ptr())
+                        .to_string_lossy()
+                        .into_owned(),
+                ),
+                _ => None,
+            }
+        }
+    }
+
+    /// Returns the number of seconds since the game became active.
+    pub fn get_seconds_since_app_active(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamUtils_GetSecondsSinceAppActive(self.utils) }
+    }
+
+    /// Returns the number of seconds since the user last used the computer.
+    ///
+    /// Useful for idle detection, e.g. to pause the game or stop matchmaking
+    /// while the user is away.
+    pub fn get_seconds_since_computer_active(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamUtils_GetSecondsSinceComputerActive(self.utils) }
+    }
+
+    /// Asks SteamVR to show the dashboard, for VR titles.
+    pub fn start_vr_dashboard(&self) {
+        unsafe { sys::SteamAPI_ISteamUtils_StartVRDashboard(self.utils) }
+    }
+
+    /// Returns whether the game is streamed to the VR headset when running
+    /// in VR mode.
+    pub fn is_vr_headset_streaming_enabled(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsVRHeadsetStreamingEnabled(self.utils) }
+    }
+
+    /// Sets whether the game is streamed to the VR headset when running in
+    /// VR mode.
+    ///
+    /// Games can disable this to show their own content in the headset, e.g.
+    /// a spectator view.
+    pub fn set_vr_headset_streaming_enabled(&self, enabled: bool) {
+        unsafe { sys::SteamAPI_ISteamUtils_SetVRHeadsetStreamingEnabled(self.utils, enabled) }
+    }
+
+    /// Checks if Steam is running in big picture mode.
+    ///
+    /// Together with
+    /// [`is_steam_running_on_steam_deck`](#method.is_steam_running_on_steam_deck)
+    /// this tells whether to switch to a controller friendly UI.
+    pub fn is_steam_in_big_picture_mode(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsSteamInBigPictureMode(self.utils) }
+    }
+
     /// Checks if Steam is running on a Steam Deck device.
     pub fn is_steam_running_on_steam_deck(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamUtils_IsSteamRunningOnSteamDeck(self.utils) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_area_corner() {
+        assert_eq!(
+            NotificationPosition::for_safe_area(0, 0, 0, 0),
+            (NotificationPosition::BottomRight, 0, 0)
+        );
+        assert_eq!(
+            NotificationPosition::for_safe_area(40, 200, 80, 10),
+            (NotificationPosition::TopLeft, 10, 40)
+        );
+        assert_eq!(
+            NotificationPosition::for_safe_area(-5, 20, 100, 300),
+            (NotificationPosition::TopRight, 20, 0)
+        );
+    }
+}